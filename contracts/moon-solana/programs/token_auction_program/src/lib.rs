@@ -1,11 +1,29 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer};
+use anchor_lang::solana_program::hash;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::{get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{default_account_state::DefaultAccountState, memo_transfer, BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+};
+use anchor_spl::token_interface::{self, Burn, Mint, TokenAccount, TokenInterface, TransferChecked};
 
 // TODO: Replace with your actual program ID after first build/deploy
 declare_id!("816xF6MFwtfBvqAJhsgbB67tgJ515jS7Ts8tbKfZ9QYw");
 
 pub const MAX_RESOURCE_NAME_LENGTH: usize = 200;
 pub const MAX_RESOURCE_VALUE_LENGTH: usize = 200;
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const DEFAULT_MIN_BID_INCREASE_BPS: u16 = 1_000; // 10%, same default as TokenAuction.sol
+pub const MAX_CRANKER_REWARD_BPS: u16 = 1_000; // Cranker can never take more than 10% of a winning bid
+pub const MAX_BURN_BPS: u16 = 9_000; // Leaves room for the largest cranker reward
+pub const DEFAULT_VETO_WINDOW: u64 = 86_400; // Moderators can veto a winning value for one day after finalization
+// Bytes kept free at the end of versioned accounts. New fields take their space from `reserved` so
+// existing accounts keep deserializing; layouts that outgrow it need a migrate_* instruction.
+pub const RESERVED_SPACE: usize = 32;
+pub const NATIVE_SOL_MINT: Pubkey = anchor_spl::token::spl_token::native_mint::ID; // Stands in for the mint of lamport bids
+pub const NATIVE_SOL_DECIMALS: u8 = 9;
+pub const MAX_SPLIT_RECIPIENTS: usize = 5;
 
 #[program]
 pub mod token_auction_program {
@@ -16,20 +34,33 @@ pub mod token_auction_program {
 
     pub fn initialize_auction(
         ctx: Context<InitializeAuction>,
+        house_id: u64,
         resource_name: String,
         default_resource_value: String,
         auction_duration: u64,
+        start_timestamp: Option<i64>, // Announce the first auction for a future time; None starts it now
+        bidding_token_price_usd: u64, // Allowlists the initial bidding mint at this price (USD, 8 decimals)
     ) -> Result<()> {
         // Validate string lengths
         require!(resource_name.len() <= MAX_RESOURCE_NAME_LENGTH, AuctionError::ResourceNameTooLong);
         require!(default_resource_value.len() <= MAX_RESOURCE_VALUE_LENGTH, AuctionError::ResourceValueTooLong);
         require!(auction_duration > 0, AuctionError::AuctionDurationTooShort);
+        require!(auction_duration <= i64::MAX as u64, AuctionError::TimestampError);
+        require!(bidding_token_price_usd > 0, AuctionError::InvalidTokenPrice);
+        require_keys_neq!(ctx.accounts.bidding_token_mint.key(), NATIVE_SOL_MINT, AuctionError::NativeMintNotAllowed);
+        require_supported_mint(&ctx.accounts.bidding_token_mint.to_account_info())?;
 
         let auction_config = &mut ctx.accounts.auction_config;
         let auction_state = &mut ctx.accounts.auction_state;
         let clock = Clock::get()?;
+        let start_timestamp = start_timestamp.unwrap_or(clock.unix_timestamp);
+        require!(start_timestamp >= clock.unix_timestamp, AuctionError::StartTimestampInPast);
 
+        auction_config.creator = ctx.accounts.authority.key();
+        auction_config.house_id = house_id;
+        auction_config.bump = ctx.bumps.auction_config;
         auction_config.authority = ctx.accounts.authority.key();
+        auction_config.treasury = ctx.accounts.authority.key(); // Until set_treasury points proceeds elsewhere
         auction_config.bidding_token_mint = ctx.accounts.bidding_token_mint.key();
         auction_config.resource_name = resource_name;
         auction_config.default_resource_value = default_resource_value;
@@ -37,13 +68,31 @@ pub mod token_auction_program {
         auction_config.current_auction_id = 1; // First auction
         auction_config.total_auctions_held = 0;
         auction_config.program_token_account_bump = ctx.bumps.program_token_account;
+        auction_config.min_bid_increase_bps = DEFAULT_MIN_BID_INCREASE_BPS;
+        auction_config.veto_window = DEFAULT_VETO_WINDOW;
+        auction_config.version = AuctionConfig::VERSION;
 
 
-        auction_state.id = 1; // Corresponds to current_auction_id
-        auction_state.start_timestamp = clock.unix_timestamp;
-        auction_state.highest_bidder = Pubkey::default(); // No bidder yet
-        auction_state.highest_bid_amount = 0;
-        auction_state.highest_bid_resource_value = "".to_string(); // Empty initially
+        auction_config.auctions_paused = false;
+        auction_config.current_auction_finalized = false;
+
+        auction_state.open(1, start_timestamp, auction_config)?; // Corresponds to current_auction_id
+
+        // The initial bidding mint is allowlisted right away so that round 1 can take bids
+        let allowed_mint = &mut ctx.accounts.allowed_mint;
+        allowed_mint.mint = ctx.accounts.bidding_token_mint.key();
+        allowed_mint.price_usd = bidding_token_price_usd;
+        allowed_mint.decimals = ctx.accounts.bidding_token_mint.decimals;
+        allowed_mint.vault_bump = ctx.bumps.program_token_account;
+        allowed_mint.bump = ctx.bumps.allowed_mint;
+        allowed_mint.rent_payer = ctx.accounts.payer.key();
+
+        emit!(AllowedMintAdded {
+            house: auction_config.key(),
+            mint: allowed_mint.mint,
+            price_usd: bidding_token_price_usd,
+            decimals: allowed_mint.decimals,
+        });
 
         msg!("Auction initialized for resource: {}", auction_config.resource_name);
         msg!("Auction duration: {} seconds", auction_config.auction_duration);
@@ -52,131 +101,415 @@ pub mod token_auction_program {
         Ok(())
     }
 
-    pub fn place_bid(
-        ctx: Context<PlaceBid>,
+    pub fn place_bid<'info>(
+        ctx: Context<'_, '_, '_, 'info, PlaceBid<'info>>,
         amount: u64,
         resource_value: String,
     ) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
         let clock = Clock::get()?;
         let auction_config = &ctx.accounts.auction_config;
         let auction_state = &mut ctx.accounts.auction_state;
+        let native_sol_bidding = auction_state.native_sol_bidding; // Fixed when the round opened
 
         // Validations
+        require!(!auction_config.auctions_paused, AuctionError::AuctionsArePaused);
         require!(resource_value.len() > 0 && resource_value.len() <= MAX_RESOURCE_VALUE_LENGTH, AuctionError::BidResourceValueEmptyOrTooLong);
-        require!(amount > auction_state.highest_bid_amount, AuctionError::BidTooLow);
-        
-        let auction_end_timestamp = auction_state.start_timestamp.checked_add(auction_config.auction_duration as i64).ok_or(AuctionError::TimestampError)?;
-        require!(clock.unix_timestamp < auction_end_timestamp, AuctionError::AuctionNotActiveOrNotEnded); // Use AuctionNotActive to match constraint
-
-        // Refund previous bidder if there was one
-        if auction_state.highest_bidder != Pubkey::default() && auction_state.highest_bid_amount > 0 {
-            // Explicitly check previous_bidder_token_account as it's UncheckedAccount
+
+        // In native SOL mode bids are lamports and none of the token accounts are passed
+        let bid_mint_key = if native_sol_bidding {
             require!(
-                !ctx.accounts.previous_bidder_token_account.data_is_empty() &&
-                ctx.accounts.previous_bidder_token_account.owner == &token::ID,
-                AuctionError::MissingOrInvalidPreviousBidderTokenAccount
+                ctx.accounts.bid_mint.is_none()
+                    && ctx.accounts.allowed_mint.is_none()
+                    && ctx.accounts.program_token_account.is_none()
+                    && ctx.accounts.bidder_token_account.is_none(),
+                AuctionError::UnexpectedBidTokenAccounts
             );
-            // Deserialize and validate the previous bidder's token account
-            let previous_bidder_account_info = ctx.accounts.previous_bidder_token_account.to_account_info();
-            let mut data = &previous_bidder_account_info.try_borrow_data()?[..];
-            let prev_bidder_token_acc = TokenAccount::try_deserialize_unchecked(&mut data)?;
+            require!(auction_config.sol_price_usd > 0, AuctionError::InvalidTokenPrice);
+            NATIVE_SOL_MINT
+        } else {
+            let allowed_mint = ctx.accounts.allowed_mint.as_ref().ok_or(AuctionError::MissingBidTokenAccounts)?;
+            let bid_mint = ctx.accounts.bid_mint.as_ref().ok_or(AuctionError::MissingBidTokenAccounts)?;
+            let program_token_account = ctx.accounts.program_token_account.as_ref().ok_or(AuctionError::MissingBidTokenAccounts)?;
+            require_keys_eq!(bid_mint.key(), allowed_mint.mint, AuctionError::InvalidBiddingMint);
+            require_keys_eq!(program_token_account.mint, allowed_mint.mint, AuctionError::MissingOrInvalidBidVault);
+            require!(allowed_mint.price_usd > 0, AuctionError::InvalidTokenPrice);
+            allowed_mint.mint
+        };
 
-            require!(prev_bidder_token_acc.mint == auction_config.bidding_token_mint, AuctionError::InvalidBiddingMint);
-            require!(prev_bidder_token_acc.owner == auction_state.highest_bidder, AuctionError::InvalidPreviousBidderTokenAccountOwner);
-            
-            let cpi_accounts_refund = Transfer {
-                from: ctx.accounts.program_token_account.to_account_info(),
-                to: previous_bidder_account_info.clone(), // Use the validated account info
-                authority: ctx.accounts.program_token_account.to_account_info(), // The PDA itself is the authority
+        require!(clock.unix_timestamp >= auction_state.start_timestamp, AuctionError::AuctionNotStarted);
+        require!(clock.unix_timestamp < auction_state.end_timestamp, AuctionError::AuctionNotActiveOrNotEnded); // Use AuctionNotActive to match constraint
+
+        // The highest bidder raising their own bid in the same mint only pays the difference
+        let is_top_up = auction_state.highest_bidder == ctx.accounts.bidder_authority.key()
+            && auction_state.highest_bid_mint == bid_mint_key;
+        let previous_amount = auction_state.highest_bid_amount;
+
+        // Transfer new bid amount (or the top-up delta) from bidder to the escrow of the bid mint
+        let transfer_amount = if is_top_up {
+            amount.checked_sub(previous_amount).ok_or(AuctionError::BidTooLow)?
+        } else {
+            amount
+        };
+        let received_amount = if native_sol_bidding {
+            // The escrow is created rent-exempt on first use; bids are only ever added on top of that reserve
+            let sol_escrow = ctx.accounts.sol_escrow.as_mut().ok_or(AuctionError::MissingSolEscrow)?;
+            sol_escrow.bump = ctx.bumps.sol_escrow.ok_or(AuctionError::MissingSolEscrow)?;
+            let cpi_accounts_bid = system_program::Transfer {
+                from: ctx.accounts.bidder_authority.to_account_info(),
+                to: sol_escrow.to_account_info(),
             };
-            
-            let program_token_account_seeds = &[
-                b"program_token_account".as_ref(),
-                &[auction_config.program_token_account_bump] // Use the stored bump
-            ];
-            let signer_seeds_refund = &[&program_token_account_seeds[..]];
-            
-            let cpi_program_refund = ctx.accounts.token_program.to_account_info();
-            let cpi_ctx_refund = CpiContext::new_with_signer(cpi_program_refund, cpi_accounts_refund, signer_seeds_refund);
-            token::transfer(cpi_ctx_refund, auction_state.highest_bid_amount)?;
+            let cpi_ctx_bid = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts_bid);
+            system_program::transfer(cpi_ctx_bid, transfer_amount)?;
+            transfer_amount
+        } else {
+            let bid_mint = ctx.accounts.bid_mint.as_ref().ok_or(AuctionError::MissingBidTokenAccounts)?;
+            let bidder_token_account = ctx.accounts.bidder_token_account.as_ref().ok_or(AuctionError::MissingBidTokenAccounts)?;
+            let program_token_account = ctx.accounts.program_token_account.as_mut().ok_or(AuctionError::MissingBidTokenAccounts)?;
+            let vault_balance_before = program_token_account.amount;
+            let cpi_accounts_bid = TransferChecked {
+                from: bidder_token_account.to_account_info(),
+                mint: bid_mint.to_account_info(),
+                to: program_token_account.to_account_info(),
+                authority: ctx.accounts.bidder_authority.to_account_info(),
+            };
+            let cpi_program_bid = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx_bid = CpiContext::new(cpi_program_bid, cpi_accounts_bid)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+            transfer_checked_with_hook(cpi_ctx_bid, transfer_amount, bid_mint.decimals)?;
+
+            // Transfer-fee mints deliver less than was sent, so the bid is credited with what the vault received
+            program_token_account.reload()?;
+            program_token_account.amount
+                .checked_sub(vault_balance_before)
+                .ok_or(ProgramError::ArithmeticOverflow)?
+        };
+        let bid_amount = if is_top_up {
+            previous_amount.checked_add(received_amount).ok_or(ProgramError::ArithmeticOverflow)?
+        } else {
+            received_amount
+        };
+
+        // Bids in different mints are compared by their normalized USD value
+        let bid_usd_value = if native_sol_bidding {
+            auction_config.sol_usd_value(bid_amount)?
+        } else {
+            ctx.accounts.allowed_mint.as_ref().ok_or(AuctionError::MissingBidTokenAccounts)?.usd_value(bid_amount)?
+        };
+        require!(bid_usd_value > auction_state.highest_bid_usd_value, AuctionError::BidTooLow);
+        if auction_state.highest_bidder == Pubkey::default() {
+            require!(bid_usd_value >= auction_state.min_opening_bid_usd, AuctionError::BelowMinimumOpeningBid);
+        }
+        if auction_state.highest_bid_usd_value > 0 {
+            let min_required_usd_value = auction_state.min_next_bid_usd_value()?;
+            require!(bid_usd_value >= min_required_usd_value, AuctionError::BidIncreaseTooLow);
+        }
+
+        // Soft close: a bid inside the final window pushes the end time out, up to the configured cap
+        let extension = auction_state.extend_for_late_bid(clock.unix_timestamp)?;
+        if extension > 0 {
+            emit!(AuctionExtended {
+                house,
+                auction_id: auction_state.id,
+                extension,
+                new_end_timestamp: auction_state.end_timestamp,
+                total_extension: auction_state.total_extension,
+            });
+            msg!("Auction {} extended by {} seconds to {}", auction_state.id, extension, auction_state.end_timestamp);
+        }
+
+        // Credit the previous bidder's refund claim; they withdraw it later with claim_refund,
+        // so bidding never depends on the state of someone else's token account
+        if !is_top_up && auction_state.highest_bidder != Pubkey::default() && auction_state.highest_bid_amount > 0 {
+            credit_refund(
+                ctx.accounts.previous_bidder_refund_claim.as_mut().ok_or(AuctionError::MissingRefundClaim)?,
+                ctx.bumps.previous_bidder_refund_claim.ok_or(AuctionError::MissingRefundClaim)?,
+                ctx.accounts.previous_bid_refund_ledger.as_mut().ok_or(AuctionError::MissingRefundClaim)?,
+                ctx.bumps.previous_bid_refund_ledger.ok_or(AuctionError::MissingRefundClaim)?,
+                auction_state.highest_bidder,
+                auction_state.highest_bid_mint,
+                auction_state.highest_bid_amount,
+                ctx.accounts.bidder_authority.key(),
+            )?;
 
             emit!(BidRefunded {
+                house,
                 auction_id: auction_state.id,
                 bidder: auction_state.highest_bidder,
+                mint: auction_state.highest_bid_mint,
                 amount: auction_state.highest_bid_amount,
             });
+        } else {
+            require!(
+                ctx.accounts.previous_bidder_refund_claim.is_none() && ctx.accounts.previous_bid_refund_ledger.is_none(),
+                AuctionError::UnexpectedRefundClaim
+            );
         }
 
-        // Transfer new bid amount from bidder to program's token account
-        let cpi_accounts_bid = Transfer {
-            from: ctx.accounts.bidder_token_account.to_account_info(),
-            to: ctx.accounts.program_token_account.to_account_info(),
-            authority: ctx.accounts.bidder_authority.to_account_info(),
-        };
-        let cpi_program_bid = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx_bid = CpiContext::new(cpi_program_bid, cpi_accounts_bid);
-        token::transfer(cpi_ctx_bid, amount)?;
-
         // Update auction state
         auction_state.highest_bidder = ctx.accounts.bidder_authority.key();
-        auction_state.highest_bid_amount = amount;
+        auction_state.highest_bid_mint = bid_mint_key;
+        auction_state.highest_bid_amount = bid_amount;
+        auction_state.highest_bid_usd_value = bid_usd_value;
         auction_state.highest_bid_resource_value = resource_value.clone();
 
-        emit!(BidPlaced {
-            auction_id: auction_state.id,
-            bidder: auction_state.highest_bidder,
-            amount: auction_state.highest_bid_amount,
-            resource_value: resource_value,
-        });
-
-        msg!("Bid placed for auction ID {}: Amount {}, Bidder {}", auction_state.id, amount, ctx.accounts.bidder_authority.key());
+        if is_top_up {
+            emit!(BidIncreased {
+                house,
+                auction_id: auction_state.id,
+                bidder: auction_state.highest_bidder,
+                mint: auction_state.highest_bid_mint,
+                previous_amount,
+                amount: auction_state.highest_bid_amount,
+                usd_value: bid_usd_value,
+                resource_value,
+            });
+            msg!("Bid increased for auction ID {}: Amount {} -> {}, Bidder {}", auction_state.id, previous_amount, bid_amount, ctx.accounts.bidder_authority.key());
+        } else {
+            emit!(BidPlaced {
+                house,
+                auction_id: auction_state.id,
+                bidder: auction_state.highest_bidder,
+                mint: auction_state.highest_bid_mint,
+                amount: auction_state.highest_bid_amount,
+                usd_value: bid_usd_value,
+                resource_value: resource_value,
+            });
+            msg!("Bid placed for auction ID {}: Amount {}, Bidder {}", auction_state.id, bid_amount, ctx.accounts.bidder_authority.key());
+        }
         Ok(())
     }
 
-    pub fn finalize_auction(ctx: Context<FinalizeAuction>) -> Result<()> {
+    pub fn finalize_auction<'info>(ctx: Context<'_, '_, '_, 'info, FinalizeAuction<'info>>) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
         let clock = Clock::get()?;
         let auction_config = &mut ctx.accounts.auction_config;
         let current_auction_state = &ctx.accounts.current_auction_state; // Not mutable here, will be closed
 
         // Validate auction has ended
-        let auction_end_timestamp = current_auction_state.start_timestamp
-            .checked_add(auction_config.auction_duration as i64)
-            .ok_or(AuctionError::TimestampError)?;
-        require!(clock.unix_timestamp >= auction_end_timestamp, AuctionError::AuctionNotYetEnded);
+        require!(clock.unix_timestamp >= current_auction_state.end_timestamp, AuctionError::AuctionNotYetEnded);
 
         let winner: Pubkey;
+        let winning_mint: Pubkey;
         let winning_amount: u64;
         let winning_resource_value: String;
+        let mut cranker_reward_share: u64 = 0;
+        let mut burned_amount: u64 = 0; // Lamport bids are never burned
+        let mut proceeds_destination = Pubkey::default(); // Stays default when nothing is paid out
+        let mut split_payouts: Vec<SplitPayout> = Vec::new();
 
-        if current_auction_state.highest_bidder != Pubkey::default() && current_auction_state.highest_bid_amount > 0 {
-            // Transfer winning bid amount to the authority's token account
-            let cpi_accounts_transfer_winnings = Transfer {
-                from: ctx.accounts.program_token_account.to_account_info(),
-                to: ctx.accounts.authority_token_account.to_account_info(),
-                authority: ctx.accounts.program_token_account.to_account_info(), // PDA is authority
-            };
-            let program_token_account_seeds = &[
-                b"program_token_account".as_ref(),
-                &[auction_config.program_token_account_bump]
-            ];
-            let signer_seeds_transfer_winnings = &[&program_token_account_seeds[..]];
-            let cpi_program_transfer_winnings = ctx.accounts.token_program.to_account_info();
-            let cpi_ctx_transfer_winnings = CpiContext::new_with_signer(
-                cpi_program_transfer_winnings,
-                cpi_accounts_transfer_winnings,
-                signer_seeds_transfer_winnings
+        let has_bid = current_auction_state.highest_bidder != Pubkey::default() && current_auction_state.highest_bid_amount > 0;
+        let reserve_not_met = has_bid && current_auction_state.highest_bid_usd_value < current_auction_state.reserve_price_usd;
+        if !reserve_not_met {
+            require!(
+                ctx.accounts.highest_bidder_refund_claim.is_none() && ctx.accounts.highest_bid_refund_ledger.is_none(),
+                AuctionError::UnexpectedRefundClaim
             );
-            token::transfer(cpi_ctx_transfer_winnings, current_auction_state.highest_bid_amount)?;
+        }
+
+        if has_bid && !reserve_not_met {
+            // With a revenue split configured the recipients' accounts come first in remaining_accounts, in
+            // table order: their wallets for native SOL, otherwise their associated token accounts for the
+            // winning mint, all writable. Any accounts after them are passed to transfer hooks. A recipient
+            // whose account is in a state the cranker cannot fix (see can_receive_lamports / can_receive_tokens)
+            // must not block the permissionless finalize, so that share goes to the treasury and is flagged.
+            let revenue_split = if auction_config.revenue_split_enabled {
+                Some(ctx.accounts.revenue_split.as_ref().ok_or(AuctionError::MissingRevenueSplit)?)
+            } else {
+                None
+            };
+            let split_account_count = revenue_split.map_or(0, |revenue_split| revenue_split.recipients.len());
+            require!(ctx.remaining_accounts.len() >= split_account_count, AuctionError::MissingSplitRecipientAccounts);
+            let (split_accounts, hook_accounts) = ctx.remaining_accounts.split_at(split_account_count);
+
+            if current_auction_state.highest_bid_mint == NATIVE_SOL_MINT {
+                // Lamport bids are paid out of the SOL escrow to the treasury wallet (or the split recipients),
+                // the cranker's share straight to the payer
+                let sol_escrow = ctx.accounts.sol_escrow.as_ref().ok_or(AuctionError::MissingSolEscrow)?;
+                cranker_reward_share = auction_config.cranker_reward_share(current_auction_state.highest_bid_amount)?;
+                let proceeds = current_auction_state.highest_bid_amount
+                    .checked_sub(cranker_reward_share)
+                    .ok_or(ProgramError::ArithmeticOverflow)?;
+                if let Some(revenue_split) = revenue_split {
+                    split_payouts = revenue_split.payouts(proceeds)?;
+                    let mut treasury_share: u64 = 0;
+                    for (payout, recipient_wallet) in split_payouts.iter_mut().zip(split_accounts) {
+                        require!(
+                            recipient_wallet.key() == payout.recipient && recipient_wallet.is_writable,
+                            AuctionError::InvalidSplitRecipientAccount
+                        );
+                        if can_receive_lamports(recipient_wallet, payout.amount)? {
+                            pay_out_sol_escrow(sol_escrow, recipient_wallet, payout.amount)?;
+                        } else {
+                            payout.paid_to_treasury = true;
+                            treasury_share = treasury_share.checked_add(payout.amount).ok_or(ProgramError::ArithmeticOverflow)?;
+                        }
+                    }
+                    if treasury_share > 0 {
+                        pay_out_sol_escrow(sol_escrow, &ctx.accounts.treasury.to_account_info(), treasury_share)?;
+                    }
+                    proceeds_destination = revenue_split.key();
+                } else {
+                    pay_out_sol_escrow(sol_escrow, &ctx.accounts.treasury.to_account_info(), proceeds)?;
+                    proceeds_destination = ctx.accounts.treasury.key();
+                }
+                if cranker_reward_share > 0 {
+                    pay_out_sol_escrow(sol_escrow, &ctx.accounts.payer.to_account_info(), cranker_reward_share)?;
+                }
+            } else {
+                // The winning bid is escrowed in the vault of the mint it was placed in
+                let program_token_account = ctx.accounts.program_token_account.as_ref().ok_or(AuctionError::MissingOrInvalidBidVault)?;
+                let program_token_account_bump = ctx.bumps.program_token_account.ok_or(AuctionError::MissingOrInvalidBidVault)?;
+                let winning_mint_account = ctx.accounts.winning_mint.as_ref().ok_or(AuctionError::MissingOrInvalidBidVault)?;
+
+                // Optional cranker reward in bidding tokens, only paid if the cranker passes a token account
+                if ctx.accounts.cranker_token_account.is_some() {
+                    cranker_reward_share = auction_config.cranker_reward_share(current_auction_state.highest_bid_amount)?;
+                }
+                burned_amount = auction_config.burn_share(current_auction_state.highest_bid_amount)?;
+                let proceeds = current_auction_state.highest_bid_amount
+                    .checked_sub(cranker_reward_share)
+                    .and_then(|amount| amount.checked_sub(burned_amount))
+                    .ok_or(ProgramError::ArithmeticOverflow)?;
+
+                // Proceeds go to the treasury's associated token account, or are split across the recipients' ones
+                let mut proceeds_transfers: Vec<(AccountInfo<'info>, u64)> = Vec::new();
+                if let Some(revenue_split) = revenue_split {
+                    split_payouts = revenue_split.payouts(proceeds)?;
+                    let mut treasury_share: u64 = 0;
+                    for (payout, recipient_token_account) in split_payouts.iter_mut().zip(split_accounts) {
+                        let expected_token_account = get_associated_token_address_with_program_id(
+                            &payout.recipient,
+                            &winning_mint_account.key(),
+                            &ctx.accounts.token_program.key(),
+                        );
+                        require!(
+                            recipient_token_account.key() == expected_token_account && recipient_token_account.is_writable,
+                            AuctionError::InvalidSplitRecipientAccount
+                        );
+                        if can_receive_tokens(recipient_token_account, &ctx.accounts.token_program.key()) {
+                            proceeds_transfers.push((recipient_token_account.clone(), payout.amount));
+                        } else {
+                            payout.paid_to_treasury = true;
+                            treasury_share = treasury_share.checked_add(payout.amount).ok_or(ProgramError::ArithmeticOverflow)?;
+                        }
+                    }
+                    if treasury_share > 0 {
+                        let treasury_token_account = ctx.accounts.treasury_token_account.as_ref().ok_or(AuctionError::InvalidProceedsDestination)?;
+                        proceeds_transfers.push((treasury_token_account.to_account_info(), treasury_share));
+                    }
+                    proceeds_destination = revenue_split.key();
+                } else {
+                    let treasury_token_account = ctx.accounts.treasury_token_account.as_ref().ok_or(AuctionError::InvalidProceedsDestination)?;
+                    proceeds_transfers.push((treasury_token_account.to_account_info(), proceeds));
+                    proceeds_destination = treasury_token_account.key();
+                }
+
+                let program_token_account_seeds = &[
+                    b"program_token_account".as_ref(),
+                    house.as_ref(),
+                    current_auction_state.highest_bid_mint.as_ref(),
+                    &[program_token_account_bump]
+                ];
+                let signer_seeds_transfer_winnings = &[&program_token_account_seeds[..]];
+
+                // The burn share is destroyed straight out of the vault, which signs as its own authority
+                if burned_amount > 0 {
+                    let cpi_accounts_burn = Burn {
+                        mint: winning_mint_account.to_account_info(),
+                        from: program_token_account.to_account_info(),
+                        authority: program_token_account.to_account_info(),
+                    };
+                    let cpi_ctx_burn = CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        cpi_accounts_burn,
+                        signer_seeds_transfer_winnings
+                    );
+                    token_interface::burn(cpi_ctx_burn, burned_amount)?;
+                }
+
+                for (destination, amount) in proceeds_transfers {
+                    if amount == 0 {
+                        continue;
+                    }
+                    let cpi_accounts_transfer_winnings = TransferChecked {
+                        from: program_token_account.to_account_info(),
+                        mint: winning_mint_account.to_account_info(),
+                        to: destination,
+                        authority: program_token_account.to_account_info(), // PDA is authority
+                    };
+                    let cpi_ctx_transfer_winnings = CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        cpi_accounts_transfer_winnings,
+                        signer_seeds_transfer_winnings
+                    ).with_remaining_accounts(hook_accounts.to_vec());
+                    transfer_checked_with_hook(cpi_ctx_transfer_winnings, amount, winning_mint_account.decimals)?;
+                }
+
+                if let Some(cranker_token_account) = &ctx.accounts.cranker_token_account {
+                    if cranker_reward_share > 0 {
+                        let cpi_accounts_cranker_reward = TransferChecked {
+                            from: program_token_account.to_account_info(),
+                            mint: winning_mint_account.to_account_info(),
+                            to: cranker_token_account.to_account_info(),
+                            authority: program_token_account.to_account_info(),
+                        };
+                        let cpi_ctx_cranker_reward = CpiContext::new_with_signer(
+                            ctx.accounts.token_program.to_account_info(),
+                            cpi_accounts_cranker_reward,
+                            signer_seeds_transfer_winnings
+                        ).with_remaining_accounts(hook_accounts.to_vec());
+                        transfer_checked_with_hook(cpi_ctx_cranker_reward, cranker_reward_share, winning_mint_account.decimals)?;
+                    }
+                }
+            }
+
+            if !split_payouts.is_empty() {
+                emit!(ProceedsSplit {
+                    house,
+                    auction_id: current_auction_state.id,
+                    mint: current_auction_state.highest_bid_mint,
+                    payouts: split_payouts.clone(),
+                });
+            }
             
             winner = current_auction_state.highest_bidder;
+            winning_mint = current_auction_state.highest_bid_mint;
             winning_amount = current_auction_state.highest_bid_amount;
             winning_resource_value = current_auction_state.highest_bid_resource_value.clone();
             msg!("Auction {} ended. Winner: {}, Amount: {}", current_auction_state.id, winner, winning_amount);
+        } else if reserve_not_met {
+            // Reserve not met: the highest bid goes back to the bidder's refund claim and the default value is shown
+            credit_refund(
+                ctx.accounts.highest_bidder_refund_claim.as_mut().ok_or(AuctionError::MissingRefundClaim)?,
+                ctx.bumps.highest_bidder_refund_claim.ok_or(AuctionError::MissingRefundClaim)?,
+                ctx.accounts.highest_bid_refund_ledger.as_mut().ok_or(AuctionError::MissingRefundClaim)?,
+                ctx.bumps.highest_bid_refund_ledger.ok_or(AuctionError::MissingRefundClaim)?,
+                current_auction_state.highest_bidder,
+                current_auction_state.highest_bid_mint,
+                current_auction_state.highest_bid_amount,
+                ctx.accounts.payer.key(),
+            )?;
+
+            emit!(BidRefunded {
+                house,
+                auction_id: current_auction_state.id,
+                bidder: current_auction_state.highest_bidder,
+                mint: current_auction_state.highest_bid_mint,
+                amount: current_auction_state.highest_bid_amount,
+            });
+
+            winner = Pubkey::default(); // No winner
+            winning_mint = Pubkey::default();
+            winning_amount = 0;
+            winning_resource_value = current_auction_state.default_resource_value.clone();
+            msg!("Auction {} ended. Reserve price not met, highest bid refunded.", current_auction_state.id);
         } else {
             // No bids, or highest bid was zero (should not happen if BidTooLow is effective)
             winner = Pubkey::default(); // No winner
+            winning_mint = Pubkey::default();
             winning_amount = 0;
-            winning_resource_value = auction_config.default_resource_value.clone();
+            winning_resource_value = current_auction_state.default_resource_value.clone();
             msg!("Auction {} ended. No bids received.", current_auction_state.id);
         }
 
@@ -184,359 +517,3384 @@ pub mod token_auction_program {
         let historical_data = &mut ctx.accounts.historical_auction_data;
         historical_data.auction_id = current_auction_state.id;
         historical_data.winner = winner;
+        historical_data.winning_mint = winning_mint;
         historical_data.winning_amount = winning_amount;
         historical_data.winning_resource_value = winning_resource_value.clone();
         historical_data.end_timestamp = clock.unix_timestamp;
+        historical_data.reserve_not_met = reserve_not_met;
+        historical_data.cancelled = false;
+        historical_data.split_payouts = split_payouts;
+        historical_data.burned_amount = burned_amount;
+        historical_data.default_resource_value = current_auction_state.default_resource_value.clone();
+        historical_data.version = HistoricalAuctionData::VERSION;
 
         // Update AuctionConfig
         auction_config.total_auctions_held = auction_config.total_auctions_held.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
-        auction_config.current_auction_id = auction_config.current_auction_id.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
 
-        // Initialize New AuctionState for the next auction
-        let new_auction_state = &mut ctx.accounts.new_auction_state;
-        new_auction_state.id = auction_config.current_auction_id;
-        new_auction_state.start_timestamp = clock.unix_timestamp;
-        new_auction_state.highest_bidder = Pubkey::default();
-        new_auction_state.highest_bid_amount = 0;
-        new_auction_state.highest_bid_resource_value = "".to_string();
+        if auction_config.auctions_paused {
+            // Keep current_auction_id on the finalized round; resume_auctions opens the next one
+            require!(ctx.accounts.new_auction_state.is_none(), AuctionError::UnexpectedNewAuctionState);
+            auction_config.current_auction_finalized = true;
+            msg!("Auctions are paused. No new auction started. Total auctions held: {}", auction_config.total_auctions_held);
+        } else {
+            // Initialize New AuctionState for the next auction
+            let new_auction_state = ctx.accounts.new_auction_state.as_mut().ok_or(AuctionError::MissingNewAuctionState)?;
+            auction_config.current_auction_id = auction_config.current_auction_id.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+            let next_start_timestamp = auction_config.next_round_start(clock.unix_timestamp)?;
+            new_auction_state.open(auction_config.current_auction_id, next_start_timestamp, auction_config)?;
+            msg!("New auction {} started. Total auctions held: {}", new_auction_state.id, auction_config.total_auctions_held);
+        }
 
         emit!(AuctionEndedEvent { // Renamed to avoid conflict with error
+            house,
             auction_id: current_auction_state.id,
             winner,
+            mint: winning_mint,
             amount: winning_amount,
             resource_value: winning_resource_value,
             end_timestamp: clock.unix_timestamp,
+            reserve_not_met,
+            proceeds_destination,
+            burned_amount,
         });
-        
-        msg!("New auction {} started. Total auctions held: {}", new_auction_state.id, auction_config.total_auctions_held);
+
+        // Optional cranker reward in lamports, paid from whatever the config PDA holds above rent exemption
+        let auction_config_info = auction_config.to_account_info();
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(auction_config_info.data_len());
+        let available_lamports = auction_config_info.lamports().saturating_sub(rent_exempt_minimum);
+        let cranker_reward_lamports = auction_config.cranker_reward_lamports.min(available_lamports);
+        if cranker_reward_lamports > 0 {
+            auction_config_info.sub_lamports(cranker_reward_lamports)?;
+            ctx.accounts.payer.add_lamports(cranker_reward_lamports)?;
+        }
+
+        if cranker_reward_lamports > 0 || cranker_reward_share > 0 {
+            emit!(CrankerRewardPaid {
+                house,
+                auction_id: current_auction_state.id,
+                cranker: ctx.accounts.payer.key(),
+                lamports: cranker_reward_lamports,
+                mint: winning_mint,
+                bid_share: cranker_reward_share,
+            });
+        }
 
         // current_auction_state account will be closed automatically by Anchor if 'close' constraint is added
         // or can be closed manually if preferred. For now, relying on 'close' in Accounts struct.
         Ok(())
     }
 
+    /// Aborts the current round while it is still running. The highest bid goes back to the bidder's
+    /// refund claim and nothing is paid out. With `pause` set (or while already paused) no new round
+    /// is opened and resume_auctions starts the next one. Once the end time has passed the round has a
+    /// legitimate outcome and can only be finalized.
+    pub fn cancel_auction(ctx: Context<CancelAuction>, reason: CancelReason, pause: bool) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        let clock = Clock::get()?;
+        let auction_config = &mut ctx.accounts.auction_config;
+        let current_auction_state = &ctx.accounts.current_auction_state; // Closed to the treasury
+        require!(clock.unix_timestamp < current_auction_state.end_timestamp, AuctionError::AuctionEnded);
+
+        let has_bid = current_auction_state.highest_bidder != Pubkey::default() && current_auction_state.highest_bid_amount > 0;
+        if has_bid {
+            credit_refund(
+                ctx.accounts.highest_bidder_refund_claim.as_mut().ok_or(AuctionError::MissingRefundClaim)?,
+                ctx.bumps.highest_bidder_refund_claim.ok_or(AuctionError::MissingRefundClaim)?,
+                ctx.accounts.highest_bid_refund_ledger.as_mut().ok_or(AuctionError::MissingRefundClaim)?,
+                ctx.bumps.highest_bid_refund_ledger.ok_or(AuctionError::MissingRefundClaim)?,
+                current_auction_state.highest_bidder,
+                current_auction_state.highest_bid_mint,
+                current_auction_state.highest_bid_amount,
+                ctx.accounts.authority.key(),
+            )?;
+
+            emit!(BidRefunded {
+                house,
+                auction_id: current_auction_state.id,
+                bidder: current_auction_state.highest_bidder,
+                mint: current_auction_state.highest_bid_mint,
+                amount: current_auction_state.highest_bid_amount,
+            });
+        } else {
+            require!(
+                ctx.accounts.highest_bidder_refund_claim.is_none() && ctx.accounts.highest_bid_refund_ledger.is_none(),
+                AuctionError::UnexpectedRefundClaim
+            );
+        }
+
+        // Cancelled rounds are recorded without a winner and show the default value
+        let historical_data = &mut ctx.accounts.historical_auction_data;
+        historical_data.auction_id = current_auction_state.id;
+        historical_data.winner = Pubkey::default();
+        historical_data.winning_mint = Pubkey::default();
+        historical_data.winning_amount = 0;
+        historical_data.winning_resource_value = current_auction_state.default_resource_value.clone();
+        historical_data.end_timestamp = clock.unix_timestamp;
+        historical_data.reserve_not_met = false;
+        historical_data.cancelled = true;
+        historical_data.default_resource_value = current_auction_state.default_resource_value.clone();
+        historical_data.version = HistoricalAuctionData::VERSION;
+
+        if pause && !auction_config.auctions_paused {
+            auction_config.auctions_paused = true;
+            emit!(AuctionsPaused {
+                house,
+                auction_id: auction_config.current_auction_id,
+            });
+        }
+
+        if auction_config.auctions_paused {
+            require!(ctx.accounts.new_auction_state.is_none(), AuctionError::UnexpectedNewAuctionState);
+            auction_config.current_auction_finalized = true;
+            msg!("Auction {} cancelled. Auctions are paused, no new auction started.", current_auction_state.id);
+        } else {
+            let new_auction_state = ctx.accounts.new_auction_state.as_mut().ok_or(AuctionError::MissingNewAuctionState)?;
+            auction_config.current_auction_id = auction_config.current_auction_id.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+            let next_start_timestamp = auction_config.next_round_start(clock.unix_timestamp)?;
+            new_auction_state.open(auction_config.current_auction_id, next_start_timestamp, auction_config)?;
+            msg!("Auction {} cancelled. New auction {} started.", current_auction_state.id, new_auction_state.id);
+        }
+
+        emit!(AuctionCancelled {
+            house,
+            auction_id: current_auction_state.id,
+            reason,
+            refunded_bidder: if has_bid { current_auction_state.highest_bidder } else { Pubkey::default() },
+            mint: current_auction_state.highest_bid_mint,
+            refunded_amount: if has_bid { current_auction_state.highest_bid_amount } else { 0 },
+            paused: auction_config.auctions_paused,
+        });
+        Ok(())
+    }
+
+    // Read-only; meant to be simulated. Anchor serializes the returned status into the
+    // transaction return data (set_return_data), so clients don't recompute timing rules.
+    pub fn get_auction_status(ctx: Context<GetAuctionStatus>) -> Result<AuctionStatus> {
+        let clock = Clock::get()?;
+        let auction_config = &ctx.accounts.auction_config;
+
+        let Some(auction_state) = &ctx.accounts.auction_state else {
+            // The current auction was finalized while paused; nothing is live until resume_auctions
+            require!(auction_config.current_auction_finalized, AuctionError::MissingAuctionState);
+            return Ok(AuctionStatus {
+                auction_id: auction_config.current_auction_id,
+                auctions_paused: auction_config.auctions_paused,
+                ..AuctionStatus::default()
+            });
+        };
+
+        let end_timestamp = auction_state.end_timestamp;
+        let time_remaining = end_timestamp.saturating_sub(clock.unix_timestamp).max(0) as u64;
+        let mut min_next_bid_usd_value = auction_state
+            .min_next_bid_usd_value()?
+            .max(auction_state.highest_bid_usd_value.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?);
+        if auction_state.highest_bidder == Pubkey::default() {
+            min_next_bid_usd_value = min_next_bid_usd_value.max(auction_state.min_opening_bid_usd);
+        }
+
+        Ok(AuctionStatus {
+            auction_id: auction_state.id,
+            start_timestamp: auction_state.start_timestamp,
+            time_remaining,
+            end_timestamp,
+            highest_bidder: auction_state.highest_bidder,
+            bidding_token_mint: auction_state.bidding_token_mint,
+            highest_bid_mint: auction_state.highest_bid_mint,
+            highest_bid_amount: auction_state.highest_bid_amount,
+            highest_bid_usd_value: auction_state.highest_bid_usd_value,
+            min_next_bid_usd_value,
+            is_finalizable: time_remaining == 0,
+            auctions_paused: auction_config.auctions_paused,
+        })
+    }
+
+    pub fn pause_auctions(ctx: Context<PauseAuctions>) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        let auction_config = &mut ctx.accounts.auction_config;
+        require!(!auction_config.auctions_paused, AuctionError::AuctionsArePaused);
+        auction_config.auctions_paused = true;
+
+        emit!(AuctionsPaused {
+            house,
+            auction_id: auction_config.current_auction_id,
+        });
+
+        msg!("Auctions paused at auction ID {}", auction_config.current_auction_id);
+        Ok(())
+    }
+
+    pub fn resume_auctions(ctx: Context<ResumeAuctions>) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        let clock = Clock::get()?;
+        let auction_config = &mut ctx.accounts.auction_config;
+        require!(auction_config.auctions_paused, AuctionError::AuctionsNotPaused);
+        auction_config.auctions_paused = false;
+
+        if auction_config.current_auction_finalized {
+            // The round was finalized while paused, so open the next one now, after the cooldown
+            let new_auction_state = ctx.accounts.new_auction_state.as_mut().ok_or(AuctionError::MissingNewAuctionState)?;
+            auction_config.current_auction_id = auction_config.current_auction_id.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+            auction_config.current_auction_finalized = false;
+            let start_timestamp = auction_config.next_round_start(clock.unix_timestamp)?;
+            new_auction_state.open(auction_config.current_auction_id, start_timestamp, auction_config)?;
+            msg!("New auction {} started at timestamp: {}", new_auction_state.id, new_auction_state.start_timestamp);
+        } else {
+            require!(ctx.accounts.new_auction_state.is_none(), AuctionError::UnexpectedNewAuctionState);
+        }
+
+        emit!(AuctionsResumed {
+            house,
+            auction_id: auction_config.current_auction_id,
+        });
+
+        msg!("Auctions resumed at auction ID {}", auction_config.current_auction_id);
+        Ok(())
+    }
+
+    pub fn claim_refund<'info>(ctx: Context<'_, '_, '_, 'info, ClaimRefund<'info>>) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        let refund_claim = &ctx.accounts.refund_claim;
+        let refund_ledger = &mut ctx.accounts.refund_ledger;
+        let amount = refund_claim.amount;
+
+        if refund_claim.mint == NATIVE_SOL_MINT {
+            let sol_escrow = ctx.accounts.sol_escrow.as_ref().ok_or(AuctionError::MissingSolEscrow)?;
+            pay_out_sol_escrow(sol_escrow, &ctx.accounts.bidder.to_account_info(), amount)?;
+        } else {
+            let program_token_account = ctx.accounts.program_token_account.as_ref().ok_or(AuctionError::MissingOrInvalidBidVault)?;
+            let program_token_account_bump = ctx.bumps.program_token_account.ok_or(AuctionError::MissingOrInvalidBidVault)?;
+            let mint = ctx.accounts.mint.as_ref().ok_or(AuctionError::MissingOrInvalidBidVault)?;
+            let destination_token_account = ctx.accounts.destination_token_account.as_ref().ok_or(AuctionError::MissingOrInvalidBidVault)?;
+            let cpi_accounts_refund = TransferChecked {
+                from: program_token_account.to_account_info(),
+                mint: mint.to_account_info(),
+                to: destination_token_account.to_account_info(),
+                authority: program_token_account.to_account_info(), // The PDA itself is the authority
+            };
+            let program_token_account_seeds = &[
+                b"program_token_account".as_ref(),
+                house.as_ref(),
+                refund_claim.mint.as_ref(),
+                &[program_token_account_bump]
+            ];
+            let signer_seeds_refund = &[&program_token_account_seeds[..]];
+            let cpi_program_refund = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx_refund = CpiContext::new_with_signer(cpi_program_refund, cpi_accounts_refund, signer_seeds_refund)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+            transfer_checked_with_hook(cpi_ctx_refund, amount, mint.decimals)?;
+        }
+
+        refund_ledger.pending_amount = refund_ledger.pending_amount.checked_sub(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+
+        emit!(RefundClaimed {
+            house,
+            bidder: refund_claim.bidder,
+            mint: refund_claim.mint,
+            amount,
+        });
+
+        msg!("Refund of {} claimed by {}", amount, refund_claim.bidder);
+        // refund_claim is closed to its rent payer by the 'close' constraint
+        Ok(())
+    }
+
+    // Permissionless: a ledger with nothing owed is closed and its rent returned to whoever funded it.
+    // The next refund in that mint creates it again.
+    pub fn close_refund_ledger(ctx: Context<CloseRefundLedger>) -> Result<()> {
+        let refund_ledger = &ctx.accounts.refund_ledger;
+        require!(refund_ledger.pending_amount == 0, AuctionError::RefundLedgerNotEmpty);
+
+        emit!(RefundLedgerClosed {
+            house: ctx.accounts.auction_config.key(),
+            mint: refund_ledger.mint,
+            rent_receiver: refund_ledger.rent_payer,
+        });
+        msg!("Refund ledger of mint {} closed", refund_ledger.mint);
+        // refund_ledger is closed to its rent payer by the 'close' constraint
+        Ok(())
+    }
+
     // --- Configuration Instructions (Authority Only) ---
 
-    pub fn set_bidding_token(ctx: Context<SetBiddingToken>, new_token_mint: Pubkey) -> Result<()> {
-        require!(new_token_mint != Pubkey::default(), AuctionError::InvalidBiddingMint);
-        ctx.accounts.auction_config.bidding_token_mint = new_token_mint;
-        msg!("Bidding token mint updated to: {}", new_token_mint);
+    // place_bid only accepts allowlisted mints, so a new default mint is allowlisted here at `price_usd`.
+    // A mint that is already allowlisted keeps its current price; set_token_price changes that.
+    pub fn set_bidding_token(ctx: Context<SetBiddingToken>, price_usd: u64) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        let auction_config = &mut ctx.accounts.auction_config;
+        let previous_mint = auction_config.bidding_token_mint;
+        let new_token_mint = ctx.accounts.new_bidding_token_mint_account.key();
+
+        // A bid escrowed in the old mint's vault must be finalized (or refunded) before switching
+        require_mint_not_in_active_bid(auction_config, &ctx.accounts.auction_state, &previous_mint)?;
+        require_keys_neq!(new_token_mint, NATIVE_SOL_MINT, AuctionError::NativeMintNotAllowed);
+        require_supported_mint(&ctx.accounts.new_bidding_token_mint_account.to_account_info())?;
+
+        let allowed_mint = &mut ctx.accounts.allowed_mint;
+        if allowed_mint.mint == Pubkey::default() {
+            require!(price_usd > 0, AuctionError::InvalidTokenPrice);
+            allowed_mint.mint = new_token_mint;
+            allowed_mint.price_usd = price_usd;
+            allowed_mint.decimals = ctx.accounts.new_bidding_token_mint_account.decimals;
+            allowed_mint.vault_bump = ctx.bumps.program_token_account;
+            allowed_mint.bump = ctx.bumps.allowed_mint;
+            allowed_mint.rent_payer = ctx.accounts.payer.key();
+
+            emit!(AllowedMintAdded {
+                house,
+                mint: new_token_mint,
+                price_usd,
+                decimals: allowed_mint.decimals,
+            });
+        }
+
+        // The mint is allowlisted, so it takes bids right away; it only becomes the default from the next round
+        auction_config.bidding_token_mint = new_token_mint;
+        auction_config.program_token_account_bump = ctx.bumps.program_token_account;
+        let effective_auction_id = auction_config.next_auction_id()?;
+
+        emit!(BiddingTokenUpdated {
+            house,
+            previous_mint,
+            new_mint: new_token_mint,
+            effective_auction_id,
+        });
+
+        msg!("Bidding token mint updated to: {}, effective from auction {}", new_token_mint, effective_auction_id);
         Ok(())
     }
 
+    // Round parameters are snapshotted into each AuctionState, so changes to them apply from the next round
+
     pub fn set_auction_duration(ctx: Context<SetAuctionDuration>, new_duration: u64) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
         require!(new_duration > 0, AuctionError::AuctionDurationTooShort);
-        ctx.accounts.auction_config.auction_duration = new_duration;
-        msg!("Auction duration updated to: {} seconds", new_duration);
+        require!(new_duration <= i64::MAX as u64, AuctionError::TimestampError);
+        let auction_config = &mut ctx.accounts.auction_config;
+        auction_config.auction_duration = new_duration;
+        let effective_auction_id = auction_config.next_auction_id()?;
+        emit!(AuctionDurationUpdated { house, auction_duration: new_duration, effective_auction_id });
+        msg!("Auction duration updated to: {} seconds, effective from auction {}", new_duration, effective_auction_id);
         Ok(())
     }
 
     pub fn set_default_resource_value(ctx: Context<SetDefaultResourceValue>, new_value: String) -> Result<()> {
         require!(!new_value.is_empty() && new_value.len() <= MAX_RESOURCE_VALUE_LENGTH, AuctionError::ResourceValueTooLong);
-        ctx.accounts.auction_config.default_resource_value = new_value.clone();
-        msg!("Default resource value updated to: {}", new_value);
+        let house = ctx.accounts.auction_config.key();
+        let auction_config = &mut ctx.accounts.auction_config;
+        auction_config.default_resource_value = new_value.clone();
+        let effective_auction_id = auction_config.next_auction_id()?;
+        emit!(DefaultResourceValueUpdated { house, default_resource_value: new_value.clone(), effective_auction_id });
+        msg!("Default resource value updated to: {}, effective from auction {}", new_value, effective_auction_id);
         Ok(())
     }
 
     pub fn set_resource_name(ctx: Context<SetResourceName>, new_name: String) -> Result<()> {
         require!(!new_name.is_empty() && new_name.len() <= MAX_RESOURCE_NAME_LENGTH, AuctionError::ResourceNameTooLong);
-        ctx.accounts.auction_config.resource_name = new_name.clone();
-        msg!("Resource name updated to: {}", new_name);
+        let house = ctx.accounts.auction_config.key();
+        let auction_config = &mut ctx.accounts.auction_config;
+        auction_config.resource_name = new_name.clone();
+        let effective_auction_id = auction_config.next_auction_id()?;
+        emit!(ResourceNameUpdated { house, resource_name: new_name.clone(), effective_auction_id });
+        msg!("Resource name updated to: {}, effective from auction {}", new_name, effective_auction_id);
         Ok(())
     }
-}
 
-// Account structures will be defined here later based on PLAN_MIGRACION.md
-// For example: AuctionConfig, AuctionState, HistoricalAuctionData
+    // Only the authority can redirect where proceeds go; the treasury may be any wallet or PDA.
+    // It must already be rent-exempt, or a lamport payout below the minimum would fail finalize_auction.
+    pub fn set_treasury(ctx: Context<SetTreasury>, new_treasury: Pubkey) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        require_keys_neq!(new_treasury, Pubkey::default(), AuctionError::InvalidTreasury);
+        let treasury = &ctx.accounts.treasury;
+        require!(
+            !treasury.executable && Rent::get()?.is_exempt(treasury.lamports(), treasury.data_len()),
+            AuctionError::TreasuryNotRentExempt
+        );
+        let auction_config = &mut ctx.accounts.auction_config;
+        let previous_treasury = auction_config.treasury;
+        auction_config.treasury = new_treasury;
 
-#[account]
-#[derive(Default)] // Added Default for easier initialization in tests if needed
-pub struct AuctionConfig {
-    pub authority: Pubkey,
-    pub bidding_token_mint: Pubkey,
-    pub resource_name: String, // Max 200 chars, space calculation needed
-    pub default_resource_value: String, // Max 200 chars, space calculation needed
-    pub auction_duration: u64, // Duration in seconds
-    pub current_auction_id: u64,
-    pub program_token_account_bump: u8, // Bump for the program's token account PDA
-    pub total_auctions_held: u64,
-}
+        emit!(TreasuryUpdated {
+            house,
+            previous_treasury,
+            new_treasury,
+        });
 
-impl AuctionConfig {
-    // Calculate space:
-    // 8 (discriminator)
-    // 32 (authority)
-    // 32 (bidding_token_mint)
-    // 4 + MAX_RESOURCE_NAME_LENGTH (resource_name)
-    // 4 + MAX_RESOURCE_VALUE_LENGTH (default_resource_value)
-    // 8 (auction_duration)
-    // 8 (current_auction_id)
-    // 1 (program_token_account_bump)
-    // 8 (total_auctions_held)
-    // = 8 + 32 + 32 + (4 + 200) + (4 + 200) + 8 + 8 + 1 + 8 = 505 bytes. Add some buffer.
-    pub const SPACE: usize = 8 + 32 + 32 + (4 + MAX_RESOURCE_NAME_LENGTH) + (4 + MAX_RESOURCE_VALUE_LENGTH) + 8 + 8 + 1 + 8 + 32 /* buffer */;
-}
+        msg!("Treasury updated to: {}", new_treasury);
+        Ok(())
+    }
 
+    // An empty table turns splitting off again, sending proceeds back to the treasury
+    pub fn set_revenue_split(ctx: Context<SetRevenueSplit>, recipients: Vec<SplitRecipient>) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        require!(recipients.len() <= MAX_SPLIT_RECIPIENTS, AuctionError::TooManySplitRecipients);
+        if !recipients.is_empty() {
+            let total_bps: u64 = recipients.iter().map(|recipient| recipient.bps as u64).sum();
+            require!(
+                total_bps == BPS_DENOMINATOR
+                    && recipients.iter().all(|recipient| recipient.bps > 0 && recipient.wallet != Pubkey::default()),
+                AuctionError::InvalidSplitShares
+            );
+        }
 
-#[account]
-#[derive(Default)]
-pub struct AuctionState {
-    pub id: u64, // ID of this auction
-    pub start_timestamp: i64,
-    pub highest_bidder: Pubkey,
-    pub highest_bid_amount: u64,
-    pub highest_bid_resource_value: String, // Max 200 chars
-    // pub is_active: bool, // Can be derived or managed explicitly. Let's derive for now.
-}
+        let revenue_split = &mut ctx.accounts.revenue_split;
+        revenue_split.recipients = recipients.clone();
+        revenue_split.bump = ctx.bumps.revenue_split;
+        revenue_split.version = RevenueSplit::VERSION;
+        ctx.accounts.auction_config.revenue_split_enabled = !recipients.is_empty();
 
-impl AuctionState {
-    // Calculate space:
-    // 8 (discriminator)
-    // 8 (id)
-    // 8 (start_timestamp)
-    // 32 (highest_bidder)
-    // 8 (highest_bid_amount)
-    // 4 + MAX_RESOURCE_VALUE_LENGTH (highest_bid_resource_value)
-    // = 8 + 8 + 8 + 32 + 8 + (4 + 200) = 268 bytes. Add some buffer.
-    pub const SPACE: usize = 8 + 8 + 8 + 32 + 8 + (4 + MAX_RESOURCE_VALUE_LENGTH) + 32 /* buffer */;
-}
+        emit!(RevenueSplitUpdated {
+            house,
+            recipients: recipients.clone(),
+        });
 
-#[account]
-#[derive(Default)]
-pub struct HistoricalAuctionData {
-    pub auction_id: u64,
-    pub winner: Pubkey, // Pubkey::default() if no winner
-    pub winning_amount: u64,
-    pub winning_resource_value: String, // Max 200 chars
-    pub end_timestamp: i64,
-}
+        msg!("Revenue split updated: {} recipients", recipients.len());
+        Ok(())
+    }
 
-impl HistoricalAuctionData {
-    // Calculate space:
-    // 8 (discriminator)
-    // 8 (auction_id)
-    // 32 (winner)
-    // 8 (winning_amount)
-    // 4 + MAX_RESOURCE_VALUE_LENGTH (winning_resource_value)
-    // 8 (end_timestamp)
-    // = 8 + 8 + 32 + 8 + (4 + 200) = 268 bytes. Add some buffer.
-    pub const SPACE: usize = 8 + 8 + 32 + 8 + (4 + MAX_RESOURCE_VALUE_LENGTH) + 32 /* buffer */;
-}
+    // Key rotation is two-step: the current authority proposes, the new key accepts by signing.
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        require!(new_authority != Pubkey::default(), AuctionError::InvalidPendingAuthority);
+        let auction_config = &mut ctx.accounts.auction_config;
+        auction_config.pending_authority = new_authority;
 
-#[derive(Accounts)]
-#[instruction(
-    resource_name: String, // Used for seed derivation if needed, and validation
-    default_resource_value: String,
-    auction_duration: u64
-)]
-pub struct InitializeAuction<'info> {
-    #[account(
+        emit!(AuthorityTransferProposed {
+            house,
+            authority: auction_config.authority,
+            pending_authority: new_authority,
+        });
+
+        msg!("Authority transfer proposed to: {}", new_authority);
+        Ok(())
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        let auction_config = &mut ctx.accounts.auction_config;
+        let previous_authority = auction_config.authority;
+        auction_config.authority = ctx.accounts.pending_authority.key();
+        auction_config.pending_authority = Pubkey::default();
+
+        emit!(AuthorityTransferAccepted {
+            house,
+            previous_authority,
+            new_authority: auction_config.authority,
+        });
+
+        msg!("Authority transferred from {} to {}", previous_authority, auction_config.authority);
+        Ok(())
+    }
+
+    pub fn cancel_authority_transfer(ctx: Context<CancelAuthorityTransfer>) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        let auction_config = &mut ctx.accounts.auction_config;
+        require!(auction_config.pending_authority != Pubkey::default(), AuctionError::NoPendingAuthority);
+        let cancelled_authority = auction_config.pending_authority;
+        auction_config.pending_authority = Pubkey::default();
+
+        emit!(AuthorityTransferCancelled {
+            house,
+            authority: auction_config.authority,
+            cancelled_authority,
+        });
+
+        msg!("Authority transfer to {} cancelled", cancelled_authority);
+        Ok(())
+    }
+
+    pub fn grant_role(ctx: Context<GrantRole>, holder: Pubkey, role: Role) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        let admin_roles = &mut ctx.accounts.admin_roles;
+        admin_roles.holder = holder;
+        admin_roles.roles |= role.mask();
+        admin_roles.bump = ctx.bumps.admin_roles;
+
+        emit!(RoleGranted { house, holder, role });
+
+        msg!("Role {:?} granted to {}", role, holder);
+        Ok(())
+    }
+
+    pub fn revoke_role(ctx: Context<RevokeRole>, holder: Pubkey, role: Role) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        let admin_roles = &mut ctx.accounts.admin_roles;
+        require!(admin_roles.has(role), AuctionError::MissingRole);
+        admin_roles.roles &= !role.mask();
+
+        emit!(RoleRevoked { house, holder, role });
+
+        msg!("Role {:?} revoked from {}", role, holder);
+        if admin_roles.roles == 0 {
+            // No roles left, return the rent to the authority
+            admin_roles.close(ctx.accounts.authority.to_account_info())?;
+        }
+        Ok(())
+    }
+
+    pub fn set_cranker_reward(ctx: Context<SetCrankerReward>, reward_lamports: u64, reward_bps: u16) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        require!(reward_bps <= MAX_CRANKER_REWARD_BPS, AuctionError::CrankerRewardTooHigh);
+        let auction_config = &mut ctx.accounts.auction_config;
+        auction_config.cranker_reward_lamports = reward_lamports;
+        auction_config.cranker_reward_bps = reward_bps;
+
+        emit!(CrankerRewardUpdated {
+            house,
+            reward_lamports,
+            reward_bps,
+        });
+
+        msg!("Cranker reward updated to: {} lamports, {} bps of the winning bid", reward_lamports, reward_bps);
+        Ok(())
+    }
+
+    // burn_bps = 0 disables burning; bids placed in native SOL are never burned
+    pub fn set_burn_bps(ctx: Context<SetBurnBps>, burn_bps: u16) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        require!(burn_bps <= MAX_BURN_BPS, AuctionError::BurnShareTooHigh);
+        ctx.accounts.auction_config.burn_bps = burn_bps;
+        emit!(BurnBpsUpdated { house, burn_bps });
+        msg!("Burn share updated to: {} bps of the winning bid", burn_bps);
+        Ok(())
+    }
+
+    // extension_window = 0 or extension_amount = 0 disables the soft close; otherwise max_total_extension must be non-zero
+    pub fn set_anti_sniping(
+        ctx: Context<SetAntiSniping>,
+        extension_window: u64,
+        extension_amount: u64,
+        max_total_extension: u64,
+    ) -> Result<()> {
+        require!(
+            extension_window <= i64::MAX as u64 && extension_amount <= i64::MAX as u64 && max_total_extension <= i64::MAX as u64,
+            AuctionError::TimestampError
+        );
+        // A zero cap would silently turn an enabled soft close into a no-op
+        require!(
+            extension_window == 0 || extension_amount == 0 || max_total_extension > 0,
+            AuctionError::InvalidAntiSnipingConfig
+        );
+        let house = ctx.accounts.auction_config.key();
+        let auction_config = &mut ctx.accounts.auction_config;
+        auction_config.extension_window = extension_window;
+        auction_config.extension_amount = extension_amount;
+        auction_config.max_total_extension = max_total_extension;
+        let effective_auction_id = auction_config.next_auction_id()?;
+
+        emit!(AntiSnipingUpdated {
+            house,
+            extension_window,
+            extension_amount,
+            max_total_extension,
+            effective_auction_id,
+        });
+
+        msg!(
+            "Anti-sniping updated: window {} s, extension {} s, max total extension {} s, effective from auction {}",
+            extension_window, extension_amount, max_total_extension, effective_auction_id
+        );
+        Ok(())
+    }
+
+    // veto_window = 0 disables vetoes of winning resource values
+    pub fn set_veto_window(ctx: Context<SetVetoWindow>, veto_window: u64) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        require!(veto_window <= i64::MAX as u64, AuctionError::TimestampError);
+        ctx.accounts.auction_config.veto_window = veto_window;
+        emit!(VetoWindowUpdated { house, veto_window });
+        msg!("Veto window updated to {} seconds", veto_window);
+        Ok(())
+    }
+
+    // round_cooldown = 0 starts the next round as soon as the previous one is finalized
+    pub fn set_round_cooldown(ctx: Context<SetRoundCooldown>, round_cooldown: u64) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        require!(round_cooldown <= i64::MAX as u64, AuctionError::TimestampError);
+        let auction_config = &mut ctx.accounts.auction_config;
+        auction_config.round_cooldown = round_cooldown;
+        let effective_auction_id = auction_config.next_auction_id()?;
+        emit!(RoundCooldownUpdated { house, round_cooldown, effective_auction_id });
+        msg!("Round cooldown updated to {} seconds, effective from auction {}", round_cooldown, effective_auction_id);
+        Ok(())
+    }
+
+    // history_retention = 0 keeps historical records forever
+    pub fn set_history_retention(ctx: Context<SetHistoryRetention>, history_retention: u64) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        require!(history_retention <= i64::MAX as u64, AuctionError::TimestampError);
+        ctx.accounts.auction_config.history_retention = history_retention;
+        emit!(HistoryRetentionUpdated { house, history_retention });
+        msg!("History retention updated to {} seconds", history_retention);
+        Ok(())
+    }
+
+    // Both values are USD with 8 decimals, compared against the normalized value of bids; 0 disables them
+    pub fn set_reserve_price(ctx: Context<SetReservePrice>, reserve_price_usd: u64, min_opening_bid_usd: u64) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        let auction_config = &mut ctx.accounts.auction_config;
+        auction_config.reserve_price_usd = reserve_price_usd;
+        auction_config.min_opening_bid_usd = min_opening_bid_usd;
+        let effective_auction_id = auction_config.next_auction_id()?;
+
+        emit!(ReservePriceUpdated {
+            house,
+            reserve_price_usd,
+            min_opening_bid_usd,
+            effective_auction_id,
+        });
+
+        msg!("Reserve price updated to {} USD, minimum opening bid to {} USD (8 decimals), effective from auction {}", reserve_price_usd, min_opening_bid_usd, effective_auction_id);
+        Ok(())
+    }
+
+    pub fn set_min_bid_increase_bps(ctx: Context<SetMinBidIncreaseBps>, new_bps: u16) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        require!(new_bps as u64 <= BPS_DENOMINATOR, AuctionError::MinBidIncreaseTooHigh);
+        let auction_config = &mut ctx.accounts.auction_config;
+        auction_config.min_bid_increase_bps = new_bps;
+        let effective_auction_id = auction_config.next_auction_id()?;
+        emit!(MinBidIncreaseUpdated { house, new_bps, effective_auction_id });
+        msg!("Minimum bid increase updated to: {} bps, effective from auction {}", new_bps, effective_auction_id);
+        Ok(())
+    }
+
+    /// Switches bidding between allowlisted mints and native SOL from the next round on. Bids
+    /// escrowed in the other currency stay where they are and are refunded or paid out in that currency.
+    pub fn set_native_sol_bidding(ctx: Context<SetNativeSolBidding>, enabled: bool) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        let auction_config = &mut ctx.accounts.auction_config;
+        if enabled {
+            require!(auction_config.sol_price_usd > 0, AuctionError::InvalidTokenPrice);
+        }
+        auction_config.native_sol_bidding = enabled;
+        let effective_auction_id = auction_config.next_auction_id()?;
+        emit!(NativeSolBiddingUpdated { house, enabled, effective_auction_id });
+        msg!("Native SOL bidding {} from auction {}", if enabled { "enabled" } else { "disabled" }, effective_auction_id);
+        Ok(())
+    }
+
+    // --- Bidding Mint Allowlist (Authority Only) ---
+
+    pub fn add_allowed_mint(ctx: Context<AddAllowedMint>, price_usd: u64) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        require!(price_usd > 0, AuctionError::InvalidTokenPrice);
+        // NATIVE_SOL_MINT identifies lamport bids, so wrapped SOL bids would share its refund claims
+        require_keys_neq!(ctx.accounts.mint.key(), NATIVE_SOL_MINT, AuctionError::NativeMintNotAllowed);
+        require_supported_mint(&ctx.accounts.mint.to_account_info())?;
+
+        let allowed_mint = &mut ctx.accounts.allowed_mint;
+        allowed_mint.mint = ctx.accounts.mint.key();
+        allowed_mint.price_usd = price_usd;
+        allowed_mint.decimals = ctx.accounts.mint.decimals;
+        allowed_mint.vault_bump = ctx.bumps.program_token_account;
+        allowed_mint.bump = ctx.bumps.allowed_mint;
+        allowed_mint.rent_payer = ctx.accounts.payer.key();
+
+        emit!(AllowedMintAdded {
+            house,
+            mint: allowed_mint.mint,
+            price_usd,
+            decimals: allowed_mint.decimals,
+        });
+
+        msg!("Mint {} allowed for bidding at {} USD (8 decimals)", allowed_mint.mint, price_usd);
+        Ok(())
+    }
+
+    pub fn remove_allowed_mint(ctx: Context<RemoveAllowedMint>) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        let mint = ctx.accounts.allowed_mint.mint;
+        // The default bidding mint must stay allowlisted so that the next round can take bids
+        require_keys_neq!(mint, ctx.accounts.auction_config.bidding_token_mint, AuctionError::CannotRemoveBiddingTokenMint);
+        require_mint_not_in_active_bid(&ctx.accounts.auction_config, &ctx.accounts.auction_state, &mint)?;
+
+        // The vault is kept so that re-allowing the mint reuses it and stray balances stay recoverable
+        emit!(AllowedMintRemoved { house, mint });
+
+        msg!("Mint {} removed from the bidding allowlist", mint);
+        Ok(())
+    }
+
+    pub fn set_token_price(ctx: Context<SetTokenPrice>, new_price_usd: u64) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        require!(new_price_usd > 0, AuctionError::InvalidTokenPrice);
+        let allowed_mint = &mut ctx.accounts.allowed_mint;
+        require_mint_not_in_active_bid(&ctx.accounts.auction_config, &ctx.accounts.auction_state, &allowed_mint.mint)?;
+
+        allowed_mint.price_usd = new_price_usd;
+
+        emit!(TokenPriceUpdated {
+            house,
+            mint: allowed_mint.mint,
+            new_price_usd,
+        });
+
+        msg!("Price for mint {} updated to {} USD (8 decimals)", allowed_mint.mint, new_price_usd);
+        Ok(())
+    }
+
+    pub fn set_sol_price(ctx: Context<SetSolPrice>, new_price_usd: u64) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        require!(new_price_usd > 0, AuctionError::InvalidTokenPrice);
+        require_mint_not_in_active_bid(&ctx.accounts.auction_config, &ctx.accounts.auction_state, &NATIVE_SOL_MINT)?;
+
+        ctx.accounts.auction_config.sol_price_usd = new_price_usd;
+
+        emit!(TokenPriceUpdated {
+            house,
+            mint: NATIVE_SOL_MINT,
+            new_price_usd,
+        });
+
+        msg!("Price for native SOL updated to {} USD (8 decimals)", new_price_usd);
+        Ok(())
+    }
+
+    // --- Treasury Instructions (Authority Only) ---
+
+    /// Withdraws tokens held by an account owned by the vault PDA of `vault_mint`: either the vault
+    /// itself (stray deposits, dust) or any other token account that PDA owns (foreign mints).
+    /// The amount escrowed for the active highest bid can never be withdrawn.
+    pub fn withdraw_token<'info>(ctx: Context<'_, '_, '_, 'info, WithdrawToken<'info>>, vault_mint: Pubkey, amount: u64) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        require!(amount > 0, AuctionError::InvalidWithdrawAmount);
+        let source_token_account = &ctx.accounts.source_token_account;
+
+        // Unlike TokenAuction.sol the bid stays escrowed after the end time until finalize_auction runs,
+        // so it is locked for as long as the current auction state holds it.
+        let locked_bid_amount = match &ctx.accounts.auction_state {
+            Some(auction_state)
+                if auction_state.is_bid_using_mint(&source_token_account.mint)
+                    && source_token_account.key() == ctx.accounts.vault_authority.key() =>
+            {
+                auction_state.highest_bid_amount
+            }
+            Some(_) => 0,
+            None => {
+                require!(ctx.accounts.auction_config.current_auction_finalized, AuctionError::MissingAuctionState);
+                0
+            }
+        };
+
+        // Unclaimed refunds of outbid bidders also stay in the vault
+        let locked_refund_amount = if source_token_account.key() == ctx.accounts.vault_authority.key() && !ctx.accounts.refund_ledger.data_is_empty() {
+            let refund_ledger_data = ctx.accounts.refund_ledger.try_borrow_data()?;
+            RefundLedger::try_deserialize(&mut &refund_ledger_data[..])?.pending_amount
+        } else {
+            0
+        };
+
+        let locked_amount = locked_bid_amount.checked_add(locked_refund_amount).ok_or(ProgramError::ArithmeticOverflow)?;
+        require!(source_token_account.amount >= locked_amount, AuctionError::LockedAmountExceedsBalance);
+        require!(amount <= source_token_account.amount - locked_amount, AuctionError::AmountExceedsWithdrawableBalance);
+
+        let cpi_accounts_withdraw = TransferChecked {
+            from: source_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.destination_token_account.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        };
+        let vault_authority_seeds = &[
+            b"program_token_account".as_ref(),
+            house.as_ref(),
+            vault_mint.as_ref(),
+            &[ctx.bumps.vault_authority]
+        ];
+        let signer_seeds_withdraw = &[&vault_authority_seeds[..]];
+        let cpi_program_withdraw = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx_withdraw = CpiContext::new_with_signer(cpi_program_withdraw, cpi_accounts_withdraw, signer_seeds_withdraw)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+        transfer_checked_with_hook(cpi_ctx_withdraw, amount, ctx.accounts.mint.decimals)?;
+
+        emit!(FundsWithdrawn {
+            house,
+            mint: source_token_account.mint,
+            to: ctx.accounts.destination_token_account.key(),
+            amount,
+        });
+
+        msg!("Withdrew {} of mint {} (locked: {})", amount, source_token_account.mint, locked_amount);
+        Ok(())
+    }
+
+    // --- Moderation Instructions ---
+
+    /// Replaces the winning resource value of a finalized auction with that round's default value while the
+    /// review window is open. A non-zero `refund_amount` is deposited from the treasury (which must
+    /// co-sign) into the winning mint's escrow and credited to the winner's refund claim.
+    pub fn veto_resource_value<'info>(
+        ctx: Context<'_, '_, '_, 'info, VetoResourceValue<'info>>,
+        auction_id: u64,
+        refund_amount: u64,
+    ) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        let clock = Clock::get()?;
+        let auction_config = &ctx.accounts.auction_config;
+        let historical_data = &mut ctx.accounts.historical_auction_data;
+
+        require!(historical_data.winner != Pubkey::default(), AuctionError::NothingToVeto);
+        require!(!historical_data.vetoed, AuctionError::AlreadyVetoed);
+        let review_deadline = historical_data.end_timestamp
+            .checked_add(auction_config.veto_window as i64)
+            .ok_or(AuctionError::TimestampError)?;
+        require!(clock.unix_timestamp <= review_deadline, AuctionError::VetoWindowClosed);
+        require!(refund_amount <= historical_data.winning_amount, AuctionError::VetoRefundTooHigh);
+
+        // The round's own default, not whatever the config holds now
+        let default_resource_value = historical_data.default_resource_value.clone();
+        let vetoed_value = std::mem::replace(&mut historical_data.winning_resource_value, default_resource_value);
+        historical_data.vetoed = true;
+
+        let mut refunded_amount = 0;
+        if refund_amount > 0 {
+            let treasury_authority = ctx.accounts.treasury_authority.as_ref().ok_or(AuctionError::MissingTreasuryAuthority)?;
+            refunded_amount = if historical_data.winning_mint == NATIVE_SOL_MINT {
+                let sol_escrow = ctx.accounts.sol_escrow.as_ref().ok_or(AuctionError::MissingSolEscrow)?;
+                let cpi_accounts_refund = system_program::Transfer {
+                    from: treasury_authority.to_account_info(),
+                    to: sol_escrow.to_account_info(),
+                };
+                let cpi_ctx_refund = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts_refund);
+                system_program::transfer(cpi_ctx_refund, refund_amount)?;
+                refund_amount
+            } else {
+                let winning_mint = ctx.accounts.winning_mint.as_ref().ok_or(AuctionError::MissingOrInvalidBidVault)?;
+                let program_token_account = ctx.accounts.program_token_account.as_mut().ok_or(AuctionError::MissingOrInvalidBidVault)?;
+                let treasury_token_account = ctx.accounts.treasury_token_account.as_ref().ok_or(AuctionError::MissingTreasuryAuthority)?;
+                let vault_balance_before = program_token_account.amount;
+                let cpi_accounts_refund = TransferChecked {
+                    from: treasury_token_account.to_account_info(),
+                    mint: winning_mint.to_account_info(),
+                    to: program_token_account.to_account_info(),
+                    authority: treasury_authority.to_account_info(),
+                };
+                let cpi_ctx_refund = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts_refund)
+                    .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+                transfer_checked_with_hook(cpi_ctx_refund, refund_amount, winning_mint.decimals)?;
+
+                // Credit what the vault actually received, as for bids in transfer-fee mints
+                program_token_account.reload()?;
+                program_token_account.amount
+                    .checked_sub(vault_balance_before)
+                    .ok_or(ProgramError::ArithmeticOverflow)?
+            };
+
+            credit_refund(
+                ctx.accounts.winner_refund_claim.as_mut().ok_or(AuctionError::MissingRefundClaim)?,
+                ctx.bumps.winner_refund_claim.ok_or(AuctionError::MissingRefundClaim)?,
+                ctx.accounts.winning_mint_refund_ledger.as_mut().ok_or(AuctionError::MissingRefundClaim)?,
+                ctx.bumps.winning_mint_refund_ledger.ok_or(AuctionError::MissingRefundClaim)?,
+                historical_data.winner,
+                historical_data.winning_mint,
+                refunded_amount,
+                ctx.accounts.authority.key(),
+            )?;
+
+            emit!(BidRefunded {
+                house,
+                auction_id,
+                bidder: historical_data.winner,
+                mint: historical_data.winning_mint,
+                amount: refunded_amount,
+            });
+        } else {
+            require!(
+                ctx.accounts.winner_refund_claim.is_none() && ctx.accounts.winning_mint_refund_ledger.is_none(),
+                AuctionError::UnexpectedRefundClaim
+            );
+        }
+        historical_data.veto_refund_amount = refunded_amount;
+
+        emit!(ResourceValueVetoed {
+            house,
+            auction_id,
+            moderator: ctx.accounts.authority.key(),
+            winner: historical_data.winner,
+            vetoed_value,
+            mint: historical_data.winning_mint,
+            refund_amount: refunded_amount,
+        });
+
+        msg!("Winning value of auction {} vetoed by {}, refunded {}", auction_id, ctx.accounts.authority.key(), refunded_amount);
+        Ok(())
+    }
+
+    // --- History Maintenance ---
+
+    /// Closes the oldest remaining historical record once it is past the retention window (and the
+    /// veto window), returning its rent to the treasury. Records are closed in auction ID order and
+    /// folded into the history summary: cumulative counts plus a hash chain over every closed record,
+    /// so archived records can still be verified against on-chain state. Permissionless.
+    pub fn close_historical_auction_data(ctx: Context<CloseHistoricalAuctionData>, auction_id: u64) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        let clock = Clock::get()?;
+        let auction_config = &ctx.accounts.auction_config;
+        let historical_data = &ctx.accounts.historical_auction_data;
+        let history_summary = &mut ctx.accounts.history_summary;
+
+        require!(auction_config.history_retention > 0, AuctionError::HistoryRetentionDisabled);
+        let next_closed_id = history_summary.closed_count.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+        require!(auction_id == next_closed_id, AuctionError::HistoryClosedOutOfOrder);
+        let retention_end = historical_data.end_timestamp
+            .checked_add(auction_config.history_retention.max(auction_config.veto_window) as i64)
+            .ok_or(AuctionError::TimestampError)?;
+        require!(clock.unix_timestamp >= retention_end, AuctionError::HistoryRetentionNotElapsed);
+
+        // chain_hash = hash(previous chain_hash || hash(borsh-serialized record))
+        let record_hash = hash::hash(&borsh::to_vec(&**historical_data)?);
+        history_summary.chain_hash = hash::hashv(&[&history_summary.chain_hash, record_hash.as_ref()]).to_bytes();
+        history_summary.closed_count = next_closed_id;
+        if historical_data.winner != Pubkey::default() {
+            history_summary.winners_count = history_summary.winners_count.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+        }
+        if historical_data.reserve_not_met {
+            history_summary.reserve_not_met_count = history_summary.reserve_not_met_count.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+        }
+        if historical_data.cancelled {
+            history_summary.cancelled_count = history_summary.cancelled_count.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+        }
+        if historical_data.vetoed {
+            history_summary.vetoed_count = history_summary.vetoed_count.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+        }
+        history_summary.last_closed_end_timestamp = historical_data.end_timestamp;
+        history_summary.bump = ctx.bumps.history_summary;
+        history_summary.version = HistorySummary::VERSION;
+
+        emit!(HistoricalRecordClosed {
+            house,
+            auction_id,
+            record_hash: record_hash.to_bytes(),
+            chain_hash: history_summary.chain_hash,
+            rent_receiver: ctx.accounts.treasury.key(),
+        });
+
+        msg!("Historical record of auction {} closed, {} closed in total", auction_id, history_summary.closed_count);
+        // historical_auction_data is closed to the treasury by the 'close' constraint
+        Ok(())
+    }
+
+    // --- Migration Instructions (Authority Only) ---
+    // Each grows an account written with an older layout version to the current SPACE and stamps the
+    // current version. Layouts only ever append fields (taken from `reserved` while it lasts), so the
+    // bytes an older account lacks read as zeros. auction_config has to be migrated first, since the
+    // other two check the authority against it. Pre-house accounts are moved with migrate_legacy_* instead.
+
+    pub fn migrate_auction_config(ctx: Context<MigrateAuctionConfig>, creator: Pubkey, house_id: u64) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        let auction_config_info = ctx.accounts.auction_config.to_account_info();
+        let mut auction_config: AuctionConfig = deserialize_padded(&auction_config_info.try_borrow_data()?, AuctionConfig::SPACE)?;
+        // Checked before growing the account, which charges the signer for the extra rent
+        require_keys_eq!(auction_config.authority, ctx.accounts.authority.key(), AuctionError::InvalidAuthority);
+        require!(auction_config.version < AuctionConfig::VERSION, AuctionError::AccountAlreadyMigrated);
+        grow_account(&auction_config_info, AuctionConfig::SPACE, &ctx.accounts.authority, &ctx.accounts.system_program)?;
+
+        let from_version = auction_config.version;
+        auction_config.version = AuctionConfig::VERSION;
+        auction_config.try_serialize(&mut &mut auction_config_info.try_borrow_mut_data()?[..])?;
+
+        emit!(AccountMigrated {
+            house,
+            account: auction_config_info.key(),
+            from_version,
+            to_version: AuctionConfig::VERSION,
+        });
+        msg!("Auction config of house {} by {} migrated from version {} to {}", house_id, creator, from_version, AuctionConfig::VERSION);
+        Ok(())
+    }
+
+    pub fn migrate_auction_state(ctx: Context<MigrateAuctionState>, auction_id: u64) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        let auction_state_info = ctx.accounts.auction_state.to_account_info();
+        let mut auction_state: AuctionState = deserialize_padded(&auction_state_info.try_borrow_data()?, AuctionState::SPACE)?;
+        require!(auction_state.version < AuctionState::VERSION, AuctionError::AccountAlreadyMigrated);
+        grow_account(&auction_state_info, AuctionState::SPACE, &ctx.accounts.authority, &ctx.accounts.system_program)?;
+
+        let from_version = auction_state.version;
+        auction_state.version = AuctionState::VERSION;
+        auction_state.try_serialize(&mut &mut auction_state_info.try_borrow_mut_data()?[..])?;
+
+        emit!(AccountMigrated {
+            house,
+            account: auction_state_info.key(),
+            from_version,
+            to_version: AuctionState::VERSION,
+        });
+        msg!("Auction state {} migrated from version {} to {}", auction_id, from_version, AuctionState::VERSION);
+        Ok(())
+    }
+
+    pub fn migrate_historical_auction_data(ctx: Context<MigrateHistoricalAuctionData>, auction_id: u64) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        let historical_data_info = ctx.accounts.historical_auction_data.to_account_info();
+        let mut historical_data: HistoricalAuctionData =
+            deserialize_padded(&historical_data_info.try_borrow_data()?, HistoricalAuctionData::SPACE)?;
+        require!(historical_data.version < HistoricalAuctionData::VERSION, AuctionError::AccountAlreadyMigrated);
+        grow_account(&historical_data_info, HistoricalAuctionData::SPACE, &ctx.accounts.authority, &ctx.accounts.system_program)?;
+
+        let from_version = historical_data.version;
+        historical_data.version = HistoricalAuctionData::VERSION;
+        historical_data.try_serialize(&mut &mut historical_data_info.try_borrow_mut_data()?[..])?;
+
+        emit!(AccountMigrated {
+            house,
+            account: historical_data_info.key(),
+            from_version,
+            to_version: HistoricalAuctionData::VERSION,
+        });
+        msg!("Historical data of auction {} migrated from version {} to {}", auction_id, from_version, HistoricalAuctionData::VERSION);
+        Ok(())
+    }
+
+    // --- Legacy Seed Migration (Authority Only) ---
+    // The first deployment kept its accounts (version 0 layouts) at PDAs without the house seed.
+    // migrate_legacy_config moves the config into a new house of its authority and marks it as the
+    // legacy house; the other instructions then move the auction states, historical records and the
+    // token vault into that house one by one and close the old accounts.
+
+    pub fn migrate_legacy_config(ctx: Context<MigrateLegacyConfig>, house_id: u64, bidding_token_price_usd: u64) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        let legacy_config_info = ctx.accounts.legacy_auction_config.to_account_info();
+        let legacy_config = AuctionConfig::from_v0(&legacy_config_info.try_borrow_data()?)?;
+        require_keys_eq!(legacy_config.authority, ctx.accounts.authority.key(), AuctionError::InvalidAuthority);
+        require_keys_eq!(legacy_config.bidding_token_mint, ctx.accounts.bidding_token_mint.key(), AuctionError::InvalidBiddingMint);
+        require!(bidding_token_price_usd > 0, AuctionError::InvalidTokenPrice);
+        // Every later round is opened with this duration, so it gets the bound set_auction_duration enforces
+        require!(legacy_config.auction_duration <= i64::MAX as u64, AuctionError::TimestampError);
+
+        let auction_config = &mut ctx.accounts.auction_config;
+        auction_config.set_inner(AuctionConfig {
+            creator: ctx.accounts.authority.key(),
+            house_id,
+            bump: ctx.bumps.auction_config,
+            program_token_account_bump: ctx.bumps.program_token_account,
+            legacy_house: true,
+            version: AuctionConfig::VERSION,
+            ..legacy_config
+        });
+
+        // Pre-house bids were priced in the bidding mint only, so it gets an allowlist entry right away
+        let allowed_mint = &mut ctx.accounts.allowed_mint;
+        allowed_mint.mint = ctx.accounts.bidding_token_mint.key();
+        allowed_mint.price_usd = bidding_token_price_usd;
+        allowed_mint.decimals = ctx.accounts.bidding_token_mint.decimals;
+        allowed_mint.vault_bump = ctx.bumps.program_token_account;
+        allowed_mint.bump = ctx.bumps.allowed_mint;
+        allowed_mint.rent_payer = ctx.accounts.authority.key();
+
+        emit!(AllowedMintAdded {
+            house,
+            mint: allowed_mint.mint,
+            price_usd: bidding_token_price_usd,
+            decimals: allowed_mint.decimals,
+        });
+
+        close_legacy_account(&legacy_config_info, &ctx.accounts.authority.to_account_info())?;
+
+        emit!(LegacyAccountMoved { house, legacy_account: legacy_config_info.key(), account: house });
+        msg!("Legacy auction config moved into house {}", house_id);
+        Ok(())
+    }
+
+    pub fn migrate_legacy_auction_state(ctx: Context<MigrateLegacyAuctionState>, auction_id: u64) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        let legacy_state_info = ctx.accounts.legacy_auction_state.to_account_info();
+        let mut auction_state = AuctionState::from_v0(
+            &legacy_state_info.try_borrow_data()?,
+            &ctx.accounts.auction_config,
+            ctx.accounts.allowed_mint.as_deref(),
+        )?;
+        // Legacy rounds carry no snapshot; they take the parameters in force now
+        auction_state.snapshot_parameters(&ctx.accounts.auction_config);
+        auction_state.version = AuctionState::VERSION;
+        ctx.accounts.auction_state.set_inner(auction_state);
+
+        close_legacy_account(&legacy_state_info, &ctx.accounts.authority.to_account_info())?;
+
+        emit!(LegacyAccountMoved { house, legacy_account: legacy_state_info.key(), account: ctx.accounts.auction_state.key() });
+        msg!("Legacy auction state {} moved into the house", auction_id);
+        Ok(())
+    }
+
+    pub fn migrate_legacy_historical_auction_data(ctx: Context<MigrateLegacyHistoricalAuctionData>, auction_id: u64) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        let legacy_data_info = ctx.accounts.legacy_historical_auction_data.to_account_info();
+        let mut historical_data = HistoricalAuctionData::from_v0(&legacy_data_info.try_borrow_data()?, &ctx.accounts.auction_config)?;
+        historical_data.version = HistoricalAuctionData::VERSION;
+        ctx.accounts.historical_auction_data.set_inner(historical_data);
+
+        close_legacy_account(&legacy_data_info, &ctx.accounts.authority.to_account_info())?;
+
+        emit!(LegacyAccountMoved {
+            house,
+            legacy_account: legacy_data_info.key(),
+            account: ctx.accounts.historical_auction_data.key(),
+        });
+        msg!("Legacy historical data of auction {} moved into the house", auction_id);
+        Ok(())
+    }
+
+    // Moves the whole balance, escrowed bids included, into the house vault of the same mint
+    pub fn migrate_legacy_vault<'info>(ctx: Context<'_, '_, '_, 'info, MigrateLegacyVault<'info>>) -> Result<()> {
+        let house = ctx.accounts.auction_config.key();
+        let mint = &ctx.accounts.mint;
+        let legacy_vault = &ctx.accounts.legacy_program_token_account;
+        let legacy_vault_seeds = &[b"program_token_account".as_ref(), &[ctx.bumps.legacy_program_token_account]];
+        let signer_seeds = &[&legacy_vault_seeds[..]];
+
+        let amount = legacy_vault.amount;
+        if amount > 0 {
+            let cpi_accounts_move = TransferChecked {
+                from: legacy_vault.to_account_info(),
+                mint: mint.to_account_info(),
+                to: ctx.accounts.program_token_account.to_account_info(),
+                authority: legacy_vault.to_account_info(), // The legacy PDA is its own authority
+            };
+            let cpi_ctx_move = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts_move, signer_seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+            transfer_checked_with_hook(cpi_ctx_move, amount, mint.decimals)?;
+        }
+
+        let cpi_accounts_close = token_interface::CloseAccount {
+            account: legacy_vault.to_account_info(),
+            destination: ctx.accounts.authority.to_account_info(),
+            authority: legacy_vault.to_account_info(),
+        };
+        token_interface::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts_close,
+            signer_seeds,
+        ))?;
+
+        emit!(LegacyAccountMoved {
+            house,
+            legacy_account: legacy_vault.key(),
+            account: ctx.accounts.program_token_account.key(),
+        });
+        msg!("Legacy vault of mint {} moved into the house with {} tokens", mint.key(), amount);
+        Ok(())
+    }
+
+}
+
+/// Credits `amount` of `mint` to `bidder`'s refund claim and records it as owed by the mint's vault.
+/// `rent_payer` funds whichever of the two accounts is created here and gets its rent back on close.
+#[allow(clippy::too_many_arguments)]
+fn credit_refund(
+    refund_claim: &mut RefundClaim,
+    refund_claim_bump: u8,
+    refund_ledger: &mut RefundLedger,
+    refund_ledger_bump: u8,
+    bidder: Pubkey,
+    mint: Pubkey,
+    amount: u64,
+    rent_payer: Pubkey,
+) -> Result<()> {
+    if refund_claim.rent_payer == Pubkey::default() {
+        refund_claim.rent_payer = rent_payer;
+    }
+    if refund_ledger.rent_payer == Pubkey::default() {
+        refund_ledger.rent_payer = rent_payer;
+    }
+    refund_claim.bidder = bidder;
+    refund_claim.mint = mint;
+    refund_claim.amount = refund_claim.amount.checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+    refund_claim.bump = refund_claim_bump;
+
+    refund_ledger.mint = mint;
+    refund_ledger.pending_amount = refund_ledger.pending_amount.checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+    refund_ledger.bump = refund_ledger_bump;
+    Ok(())
+}
+
+/// Fails if the current auction escrows a bid in `mint`. The auction state may only be omitted
+/// when the current auction was already finalized while paused.
+fn require_mint_not_in_active_bid(
+    auction_config: &AuctionConfig,
+    auction_state: &Option<Account<AuctionState>>,
+    mint: &Pubkey,
+) -> Result<()> {
+    match auction_state {
+        Some(auction_state) => require!(!auction_state.is_bid_using_mint(mint), AuctionError::MintUsedByActiveBid),
+        None => require!(auction_config.current_auction_finalized, AuctionError::MissingAuctionState),
+    }
+    Ok(())
+}
+
+/// Pays `lamports` escrowed for bids out of the program-owned SOL escrow. Only bid amounts are
+/// ever moved, so the escrow keeps the rent-exempt balance it was created with.
+fn pay_out_sol_escrow(sol_escrow: &Account<SolEscrow>, to: &AccountInfo, lamports: u64) -> Result<()> {
+    let escrow_info = sol_escrow.to_account_info();
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(escrow_info.data_len());
+    require!(
+        escrow_info.lamports().checked_sub(lamports).is_some_and(|remaining| remaining >= rent_exempt_minimum),
+        AuctionError::SolEscrowBelowRentExemption
+    );
+    escrow_info.sub_lamports(lamports)?;
+    to.add_lamports(lamports)?;
+    Ok(())
+}
+
+/// Grows `account` to `space` bytes if it was created with an older, smaller layout. The new bytes
+/// are zeroed, so fields appended since deserialize to their defaults; `payer` covers the extra rent.
+fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    space: usize,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    if account.data_len() >= space {
+        return Ok(());
+    }
+    let rent_shortfall = Rent::get()?.minimum_balance(space).saturating_sub(account.lamports());
+    if rent_shortfall > 0 {
+        let cpi_accounts_rent = system_program::Transfer {
+            from: payer.to_account_info(),
+            to: account.clone(),
+        };
+        system_program::transfer(CpiContext::new(system_program.to_account_info(), cpi_accounts_rent), rent_shortfall)?;
+    }
+    account.realloc(space, true)?;
+    Ok(())
+}
+
+/// Whether a split recipient's wallet can be credited `lamports` without being left below rent
+/// exemption, which the runtime would reject.
+fn can_receive_lamports(wallet: &AccountInfo, lamports: u64) -> Result<bool> {
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(wallet.data_len());
+    Ok(lamports == 0
+        || (!wallet.executable
+            && wallet.lamports().checked_add(lamports).is_some_and(|balance| balance >= rent_exempt_minimum)))
+}
+
+/// Whether a split recipient's token account can be transferred to: it has to be owned by
+/// `token_program`, initialized and not frozen (a closed account is owned by the system program),
+/// and must not require incoming transfers to carry a memo, which the finalize transfer has none of.
+fn can_receive_tokens(token_account: &AccountInfo, token_program: &Pubkey) -> bool {
+    if token_account.owner != token_program {
+        return false;
+    }
+    let Ok(token_account_data) = token_account.try_borrow_data() else {
+        return false;
+    };
+    StateWithExtensions::<spl_token_2022::state::Account>::unpack(&token_account_data).is_ok_and(|token_account_state| {
+        token_account_state.base.state == spl_token_2022::state::AccountState::Initialized
+            && !memo_transfer::memo_required(&token_account_state)
+    })
+}
+
+/// Closes an account at pre-house seeds that is read without an Anchor account type, the way the
+/// 'close' constraint does: its lamports go to `receiver` and it is handed back to the system program.
+fn close_legacy_account<'info>(account: &AccountInfo<'info>, receiver: &AccountInfo<'info>) -> Result<()> {
+    receiver.add_lamports(account.lamports())?;
+    account.sub_lamports(account.lamports())?;
+    account.assign(&System::id());
+    account.realloc(0, false)?;
+    Ok(())
+}
+
+/// Value of `amount` base units in USD with 8 decimals, given the price of one whole token.
+fn usd_value(amount: u64, price_usd: u64, decimals: u8) -> Result<u64> {
+    let value = (amount as u128)
+        .checked_mul(price_usd as u128)
+        .ok_or(ProgramError::ArithmeticOverflow)?
+        .checked_div(10u128.checked_pow(decimals as u32).ok_or(ProgramError::ArithmeticOverflow)?)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    Ok(u64::try_from(value).map_err(|_| ProgramError::ArithmeticOverflow)?)
+}
+
+/// Rejects Token-2022 mints whose extensions would break escrow in the program's vaults:
+/// non-transferable tokens, confidential-only supply, permanent delegates that can drain a vault
+/// and a frozen default account state, which would create the vault frozen.
+fn require_supported_mint(mint: &AccountInfo) -> Result<()> {
+    if mint.owner != &spl_token_2022::ID {
+        return Ok(());
+    }
+    let mint_data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let extension_types = mint_state.get_extension_types()?;
+    require!(
+        !extension_types.iter().any(|extension_type| matches!(
+            extension_type,
+            ExtensionType::NonTransferable | ExtensionType::ConfidentialMintBurn | ExtensionType::PermanentDelegate
+        )),
+        AuctionError::UnsupportedMintExtension
+    );
+    require!(
+        !mint_state
+            .get_extension::<DefaultAccountState>()
+            .is_ok_and(|default_account_state| default_account_state.state == spl_token_2022::state::AccountState::Frozen as u8),
+        AuctionError::UnsupportedMintExtension
+    );
+    Ok(())
+}
+
+/// `transfer_checked` that forwards the CPI context's remaining accounts, so mints with a
+/// transfer hook can resolve the hook program's extra accounts.
+fn transfer_checked_with_hook<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    spl_token_2022::onchain::invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        &ctx.remaining_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
+
+// Account structures will be defined here later based on PLAN_MIGRACION.md
+// For example: AuctionConfig, AuctionState, HistoricalAuctionData
+
+#[account]
+#[derive(Default)] // Added Default for easier initialization in tests if needed
+pub struct AuctionConfig {
+    pub authority: Pubkey,
+    pub bidding_token_mint: Pubkey, // Default bidding mint; bids are accepted in any allowlisted mint
+    pub resource_name: String, // Max 200 chars, space calculation needed
+    pub default_resource_value: String, // Max 200 chars, space calculation needed
+    pub auction_duration: u64, // Duration in seconds
+    pub current_auction_id: u64,
+    pub program_token_account_bump: u8, // Bump for the vault PDA of bidding_token_mint
+    pub total_auctions_held: u64,
+    pub min_bid_increase_bps: u16, // Minimum raise over the highest bid's USD value, in basis points
+    pub auctions_paused: bool, // While paused no bids are accepted and finalizing does not start a new auction
+    pub current_auction_finalized: bool, // True if current_auction_id was finalized while paused
+    pub cranker_reward_lamports: u64, // Paid to whoever finalizes, from lamports deposited on this PDA
+    pub cranker_reward_bps: u16, // Share of the winning bid paid to whoever finalizes
+    pub pending_authority: Pubkey, // Proposed new authority, Pubkey::default() if none
+    pub extension_window: u64, // Bids placed within this many seconds of the end extend the auction
+    pub extension_amount: u64, // Seconds added to the end time per late bid
+    pub max_total_extension: u64, // Cap on the seconds an auction can be extended in total
+    pub reserve_price_usd: u64, // Winning bids below this USD value (8 decimals) are refunded at finalization
+    pub min_opening_bid_usd: u64, // Minimum USD value (8 decimals) of the first bid of an auction
+    pub native_sol_bidding: bool, // Bids are placed in lamports into the SOL escrow instead of allowlisted mints
+    pub sol_price_usd: u64, // Reference price of one SOL in USD with 8 decimals
+    pub veto_window: u64, // Seconds after finalization during which a moderator can veto the winning value
+    pub version: u8, // Layout version, AuctionConfig::VERSION once created or migrated; 0 for pre-house accounts
+    pub history_retention: u64, // Seconds a historical record is kept before it can be closed, 0 keeps records forever
+    pub round_cooldown: u64, // Seconds between finalizing (or cancelling) a round and the start of the next one
+    pub revenue_split_enabled: bool, // Proceeds are split per the RevenueSplit table instead of going to the treasury
+    pub burn_bps: u16, // Share of a winning token bid burned at finalization
+    pub legacy_house: bool, // Created by migrate_legacy_config; the only house that can take over pre-house accounts
+    pub creator: Pubkey, // Signer that created this auction house; seeds the config PDA together with house_id
+    pub house_id: u64, // Chosen by the creator to tell their houses apart (one per resource)
+    pub bump: u8,
+    pub treasury: Pubkey, // Receives auction proceeds (in its associated token accounts) and reclaimed history rent
+    pub reserved: [u8; RESERVED_SPACE],
+}
+
+impl AuctionConfig {
+    // Calculate space:
+    // 8 (discriminator)
+    // 32 (authority)
+    // 32 (bidding_token_mint)
+    // 4 + MAX_RESOURCE_NAME_LENGTH (resource_name)
+    // 4 + MAX_RESOURCE_VALUE_LENGTH (default_resource_value)
+    // 8 (auction_duration)
+    // 8 (current_auction_id)
+    // 1 (program_token_account_bump)
+    // 8 (total_auctions_held)
+    // 2 (min_bid_increase_bps)
+    // 1 (auctions_paused)
+    // 1 (current_auction_finalized)
+    // 8 (cranker_reward_lamports)
+    // 2 (cranker_reward_bps)
+    // 32 (pending_authority)
+    // 8 (extension_window)
+    // 8 (extension_amount)
+    // 8 (max_total_extension)
+    // 8 (reserve_price_usd)
+    // 8 (min_opening_bid_usd)
+    // 1 (native_sol_bidding)
+    // 8 (sol_price_usd)
+    // 8 (veto_window)
+    // 1 (version)
+    // 8 (history_retention)
+    // 8 (round_cooldown)
+    // 1 (revenue_split_enabled)
+    // 2 (burn_bps)
+    // 1 (legacy_house)
+    // 32 (creator)
+    // 8 (house_id)
+    // 1 (bump)
+    // 32 (treasury)
+    // 32 (reserved)
+    // = 8 + 32 + 32 + (4 + 200) + (4 + 200) + 8 + 8 + 1 + 8 + 2 + 1 + 1 + 8 + 2 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 8 + 1 + 8 + 8 + 1 + 2 + 1 + 32 + 8 + 1 + 32 + 32 = 734 bytes.
+    pub const SPACE: usize = 8 + 32 + 32 + (4 + MAX_RESOURCE_NAME_LENGTH) + (4 + MAX_RESOURCE_VALUE_LENGTH) + 8 + 8 + 1 + 8 + 2 + 1 + 1 + 8 + 2 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 8 + 1 + 8 + 8 + 1 + 2 + 1 + 32 + 8 + 1 + 32 + RESERVED_SPACE;
+    pub const VERSION: u8 = 1;
+
+    /// The authority implicitly holds every role; other signers need an `AdminRoles` grant.
+    pub fn has_role(&self, signer: &Pubkey, admin_roles: Option<&AdminRoles>, role: Role) -> bool {
+        *signer == self.authority
+            || admin_roles.is_some_and(|admin_roles| admin_roles.holder == *signer && admin_roles.has(role))
+    }
+
+    /// Share of `winning_amount` paid to the cranker, in the units of the winning bid, rounded down.
+    pub fn cranker_reward_share(&self, winning_amount: u64) -> Result<u64> {
+        let reward = (winning_amount as u128)
+            .checked_mul(self.cranker_reward_bps as u128)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            / BPS_DENOMINATOR as u128;
+        Ok(reward as u64) // Never exceeds winning_amount since cranker_reward_bps <= BPS_DENOMINATOR
+    }
+
+    /// Share of `winning_amount` burned at finalization, rounded down.
+    pub fn burn_share(&self, winning_amount: u64) -> Result<u64> {
+        let burned = (winning_amount as u128)
+            .checked_mul(self.burn_bps as u128)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            / BPS_DENOMINATOR as u128;
+        Ok(burned as u64) // burn_bps + cranker_reward_bps <= BPS_DENOMINATOR, so proceeds never go negative
+    }
+
+    /// First auction that picks up a round parameter changed now.
+    pub fn next_auction_id(&self) -> Result<u64> {
+        Ok(self.current_auction_id.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?)
+    }
+
+    /// Start time of a round opened at `now`, after the configured cooldown.
+    pub fn next_round_start(&self, now: i64) -> Result<i64> {
+        Ok(now.checked_add(self.round_cooldown as i64).ok_or(AuctionError::TimestampError)?)
+    }
+
+    /// Value of `lamports` in USD with 8 decimals.
+    pub fn sol_usd_value(&self, lamports: u64) -> Result<u64> {
+        usd_value(lamports, self.sol_price_usd, NATIVE_SOL_DECIMALS)
+    }
+}
+
+
+#[account]
+#[derive(Default)]
+pub struct AuctionState {
+    pub id: u64, // ID of this auction
+    pub start_timestamp: i64,
+    pub end_timestamp: i64, // start_timestamp + auction_duration, pushed out by late bids
+    pub total_extension: u64, // Seconds added to the end time by late bids so far
+    pub highest_bidder: Pubkey,
+    pub highest_bid_mint: Pubkey, // Allowlisted mint the highest bid was placed in
+    pub highest_bid_amount: u64, // In base units of highest_bid_mint
+    pub highest_bid_usd_value: u64, // Normalized USD value (8 decimals) used to compare bids
+    pub highest_bid_resource_value: String, // Max 200 chars
+    // pub is_active: bool, // Can be derived or managed explicitly. Let's derive for now.
+    pub version: u8, // Layout version, AuctionState::VERSION once opened or migrated
+    // Round parameters copied from the config when the round opened
+    pub auction_duration: u64, // Duration the round opened with, before late-bid extensions
+    pub native_sol_bidding: bool, // Bids in this round are lamports instead of allowlisted mints
+    pub resource_name: String, // Max 200 chars
+    pub min_bid_increase_bps: u16,
+    pub extension_window: u64,
+    pub extension_amount: u64,
+    pub max_total_extension: u64,
+    pub reserve_price_usd: u64,
+    pub min_opening_bid_usd: u64,
+    pub bidding_token_mint: Pubkey, // Default bidding mint of the config when the round opened
+    pub default_resource_value: String, // Max 200 chars; shown when the round ends without a winner
+    pub reserved: [u8; RESERVED_SPACE],
+}
+
+impl AuctionState {
+    // Calculate space:
+    // 8 (discriminator)
+    // 8 (id)
+    // 8 (start_timestamp)
+    // 8 (end_timestamp)
+    // 8 (total_extension)
+    // 32 (highest_bidder)
+    // 32 (highest_bid_mint)
+    // 8 (highest_bid_amount)
+    // 8 (highest_bid_usd_value)
+    // 4 + MAX_RESOURCE_VALUE_LENGTH (highest_bid_resource_value)
+    // 1 (version)
+    // 8 (auction_duration)
+    // 1 (native_sol_bidding)
+    // 4 + MAX_RESOURCE_NAME_LENGTH (resource_name)
+    // 2 (min_bid_increase_bps)
+    // 8 (extension_window)
+    // 8 (extension_amount)
+    // 8 (max_total_extension)
+    // 8 (reserve_price_usd)
+    // 8 (min_opening_bid_usd)
+    // 32 (bidding_token_mint)
+    // 4 + MAX_RESOURCE_VALUE_LENGTH (default_resource_value)
+    // 32 (reserved)
+    // = 8 + 8 + 8 + 8 + 8 + 32 + 32 + 8 + 8 + (4 + 200) + 1 + 8 + 1 + (4 + 200) + 2 + 8 + 8 + 8 + 8 + 8 + 32 + (4 + 200) + 32 = 848 bytes.
+    pub const SPACE: usize = 8 + 8 + 8 + 8 + 8 + 32 + 32 + 8 + 8 + (4 + MAX_RESOURCE_VALUE_LENGTH) + 1
+        + 8 + 1 + (4 + MAX_RESOURCE_NAME_LENGTH) + 2 + 8 + 8 + 8 + 8 + 8 + 32 + (4 + MAX_RESOURCE_VALUE_LENGTH) + RESERVED_SPACE;
+    pub const VERSION: u8 = 1;
+
+    /// Resets this account as a fresh auction with no bids, running on the config's current parameters.
+    pub fn open(&mut self, id: u64, start_timestamp: i64, auction_config: &AuctionConfig) -> Result<()> {
+        self.snapshot_parameters(auction_config);
+        self.id = id;
+        self.start_timestamp = start_timestamp;
+        let auction_duration = i64::try_from(self.auction_duration).map_err(|_| AuctionError::TimestampError)?;
+        self.end_timestamp = start_timestamp.checked_add(auction_duration).ok_or(AuctionError::TimestampError)?;
+        self.total_extension = 0;
+        self.highest_bidder = Pubkey::default(); // No bidder yet
+        self.highest_bid_mint = Pubkey::default();
+        self.highest_bid_amount = 0;
+        self.highest_bid_usd_value = 0;
+        self.highest_bid_resource_value = "".to_string(); // Empty initially
+        self.version = Self::VERSION;
+        Ok(())
+    }
+
+    /// Copies the parameters a running round must not see change; config updates apply from the next round.
+    pub fn snapshot_parameters(&mut self, auction_config: &AuctionConfig) {
+        self.auction_duration = auction_config.auction_duration;
+        self.native_sol_bidding = auction_config.native_sol_bidding;
+        self.resource_name = auction_config.resource_name.clone();
+        self.min_bid_increase_bps = auction_config.min_bid_increase_bps;
+        self.extension_window = auction_config.extension_window;
+        self.extension_amount = auction_config.extension_amount;
+        self.max_total_extension = auction_config.max_total_extension;
+        self.reserve_price_usd = auction_config.reserve_price_usd;
+        self.min_opening_bid_usd = auction_config.min_opening_bid_usd;
+        self.bidding_token_mint = auction_config.bidding_token_mint;
+        self.default_resource_value = auction_config.default_resource_value.clone();
+    }
+
+    /// Smallest USD value a bid must reach to outbid the highest bid, rounded up
+    /// the same way as `TokenAuction.placeBid`.
+    pub fn min_next_bid_usd_value(&self) -> Result<u64> {
+        let bps_denominator = BPS_DENOMINATOR as u128;
+        let min_required = (self.highest_bid_usd_value as u128)
+            .checked_mul(bps_denominator + self.min_bid_increase_bps as u128)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            .checked_add(bps_denominator - 1)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            / bps_denominator;
+        Ok(u64::try_from(min_required).map_err(|_| ProgramError::ArithmeticOverflow)?)
+    }
+
+    /// Pushes the end time out if a bid at `now` lands inside the extension window.
+    /// Returns the number of seconds added, which is 0 once `max_total_extension` is used up.
+    pub fn extend_for_late_bid(&mut self, now: i64) -> Result<u64> {
+        if self.extension_window == 0 || self.extension_amount == 0 {
+            return Ok(0);
+        }
+        let time_remaining = self.end_timestamp.checked_sub(now).ok_or(AuctionError::TimestampError)?;
+        if time_remaining as u64 > self.extension_window {
+            return Ok(0);
+        }
+        let extension = self.extension_amount
+            .min(self.max_total_extension.saturating_sub(self.total_extension));
+        if extension > 0 {
+            self.end_timestamp = self.end_timestamp.checked_add(extension as i64).ok_or(AuctionError::TimestampError)?;
+            self.total_extension = self.total_extension.checked_add(extension).ok_or(ProgramError::ArithmeticOverflow)?;
+        }
+        Ok(extension)
+    }
+
+    /// Whether this auction currently escrows a bid placed in `mint`.
+    pub fn is_bid_using_mint(&self, mint: &Pubkey) -> bool {
+        self.highest_bidder != Pubkey::default() && self.highest_bid_mint == *mint
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct HistoricalAuctionData {
+    pub auction_id: u64,
+    pub winner: Pubkey, // Pubkey::default() if no winner
+    pub winning_mint: Pubkey, // Pubkey::default() if no winner
+    pub winning_amount: u64,
+    pub winning_resource_value: String, // Max 200 chars
+    pub end_timestamp: i64,
+    pub reserve_not_met: bool, // True if the highest bid was below the reserve price and refunded
+    pub cancelled: bool, // True if the round was aborted with cancel_auction and the highest bid refunded
+    pub vetoed: bool, // True if a moderator replaced the winning value with the default value
+    pub veto_refund_amount: u64, // Part of the winning bid refunded to the winner on veto, in winning_mint base units
+    pub version: u8, // Layout version, HistoricalAuctionData::VERSION once written or migrated
+    pub burned_amount: u64, // Part of the winning bid burned at finalization, in winning_mint base units
+    pub split_payouts: Vec<SplitPayout>, // Per-recipient proceeds when a revenue split applied, max MAX_SPLIT_RECIPIENTS
+    pub default_resource_value: String, // Max 200 chars; the round's default value, which a veto puts in place of the winning value
+    pub reserved: [u8; RESERVED_SPACE],
+}
+
+impl HistoricalAuctionData {
+    // Calculate space:
+    // 8 (discriminator)
+    // 8 (auction_id)
+    // 32 (winner)
+    // 32 (winning_mint)
+    // 8 (winning_amount)
+    // 4 + MAX_RESOURCE_VALUE_LENGTH (winning_resource_value)
+    // 8 (end_timestamp)
+    // 1 (reserve_not_met)
+    // 1 (cancelled)
+    // 1 (vetoed)
+    // 8 (veto_refund_amount)
+    // 1 (version)
+    // 8 (burned_amount)
+    // 4 + MAX_SPLIT_RECIPIENTS * (32 + 8 + 1) (split_payouts)
+    // 4 + MAX_RESOURCE_VALUE_LENGTH (default_resource_value)
+    // 32 (reserved)
+    // = 8 + 8 + 32 + 32 + 8 + (4 + 200) + 8 + 1 + 1 + 1 + 8 + 1 + 8 + (4 + 5 * 41) + (4 + 200) + 32 = 765 bytes.
+    pub const SPACE: usize = 8 + 8 + 32 + 32 + 8 + (4 + MAX_RESOURCE_VALUE_LENGTH) + 8 + 1 + 1 + 1 + 8 + 1 + 8
+        + (4 + MAX_SPLIT_RECIPIENTS * (32 + 8 + 1)) + (4 + MAX_RESOURCE_VALUE_LENGTH) + RESERVED_SPACE;
+    pub const VERSION: u8 = 1;
+}
+
+// --- Legacy Layouts ---
+// Accounts as the first deployment created them (version 0), at the pre-house seeds. The
+// migrate_legacy_* instructions read them with these structs and convert them field by field, since
+// the current state and history layouts insert fields in the middle and a version 0 config lacks defaults.
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct AuctionConfigV0 {
+    pub authority: Pubkey,
+    pub bidding_token_mint: Pubkey,
+    pub resource_name: String,
+    pub default_resource_value: String,
+    pub auction_duration: u64,
+    pub current_auction_id: u64,
+    pub program_token_account_bump: u8,
+    pub total_auctions_held: u64,
+}
+
+impl AuctionConfigV0 {
+    // 8 + 32 + 32 + (4 + 200) + (4 + 200) + 8 + 8 + 1 + 8 + 32 (buffer) = 537 bytes.
+    pub const SPACE: usize = 8 + 32 + 32 + (4 + MAX_RESOURCE_NAME_LENGTH) + (4 + MAX_RESOURCE_VALUE_LENGTH) + 8 + 8 + 1 + 8 + 32;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct AuctionStateV0 {
+    pub id: u64,
+    pub start_timestamp: i64,
+    pub highest_bidder: Pubkey,
+    pub highest_bid_amount: u64,
+    pub highest_bid_resource_value: String,
+}
+
+impl AuctionStateV0 {
+    // 8 + 8 + 8 + 32 + 8 + (4 + 200) + 32 (buffer) = 300 bytes.
+    pub const SPACE: usize = 8 + 8 + 8 + 32 + 8 + (4 + MAX_RESOURCE_VALUE_LENGTH) + 32;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct HistoricalAuctionDataV0 {
+    pub auction_id: u64,
+    pub winner: Pubkey,
+    pub winning_amount: u64,
+    pub winning_resource_value: String,
+    pub end_timestamp: i64,
+}
+
+impl HistoricalAuctionDataV0 {
+    // 8 + 8 + 32 + 8 + (4 + 200) + 8 + 32 (buffer) = 300 bytes.
+    pub const SPACE: usize = 8 + 8 + 32 + 8 + (4 + MAX_RESOURCE_VALUE_LENGTH) + 8 + 32;
+}
+
+impl AuctionConfig {
+    /// Converts a version 0 config, giving the fields it lacks the defaults `initialize_auction` sets.
+    pub fn from_v0(data: &[u8]) -> Result<Self> {
+        let v0: AuctionConfigV0 = deserialize_v0(data, Self::DISCRIMINATOR)?;
+        Ok(AuctionConfig {
+            authority: v0.authority,
+            bidding_token_mint: v0.bidding_token_mint,
+            resource_name: v0.resource_name,
+            default_resource_value: v0.default_resource_value,
+            auction_duration: v0.auction_duration,
+            current_auction_id: v0.current_auction_id,
+            program_token_account_bump: v0.program_token_account_bump,
+            total_auctions_held: v0.total_auctions_held,
+            min_bid_increase_bps: DEFAULT_MIN_BID_INCREASE_BPS,
+            veto_window: DEFAULT_VETO_WINDOW,
+            treasury: v0.authority, // Proceeds went to the authority before set_treasury existed
+            version: 0,
+            ..Default::default()
+        })
+    }
+}
+
+impl AuctionState {
+    /// Converts a version 0 auction state. Those rounds took bids in the config's bidding mint only,
+    /// so their highest bid is priced with that mint's allowlist entry, required when there is a bid.
+    pub fn from_v0(data: &[u8], auction_config: &AuctionConfig, bidding_mint_entry: Option<&AllowedMint>) -> Result<Self> {
+        let v0: AuctionStateV0 = deserialize_v0(data, Self::DISCRIMINATOR)?;
+        let auction_duration = i64::try_from(auction_config.auction_duration).map_err(|_| AuctionError::TimestampError)?;
+        let has_bid = v0.highest_bidder != Pubkey::default();
+        let highest_bid_usd_value = if has_bid {
+            bidding_mint_entry.ok_or(AuctionError::MissingBidTokenAccounts)?.usd_value(v0.highest_bid_amount)?
+        } else {
+            0
+        };
+        Ok(AuctionState {
+            id: v0.id,
+            start_timestamp: v0.start_timestamp,
+            end_timestamp: v0.start_timestamp
+                .checked_add(auction_duration)
+                .ok_or(AuctionError::TimestampError)?,
+            highest_bidder: v0.highest_bidder,
+            highest_bid_mint: if has_bid { auction_config.bidding_token_mint } else { Pubkey::default() },
+            highest_bid_amount: v0.highest_bid_amount,
+            highest_bid_usd_value,
+            highest_bid_resource_value: v0.highest_bid_resource_value,
+            version: 0,
+            ..Default::default()
+        })
+    }
+}
+
+impl HistoricalAuctionData {
+    /// Converts a version 0 historical record. Version 0 winners always paid in the config's bidding mint.
+    pub fn from_v0(data: &[u8], auction_config: &AuctionConfig) -> Result<Self> {
+        let v0: HistoricalAuctionDataV0 = deserialize_v0(data, Self::DISCRIMINATOR)?;
+        let has_winner = v0.winner != Pubkey::default();
+        Ok(HistoricalAuctionData {
+            auction_id: v0.auction_id,
+            winner: v0.winner,
+            winning_mint: if has_winner { auction_config.bidding_token_mint } else { Pubkey::default() },
+            winning_amount: v0.winning_amount,
+            winning_resource_value: v0.winning_resource_value,
+            end_timestamp: v0.end_timestamp,
+            default_resource_value: auction_config.default_resource_value.clone(), // Version 0 rounds had no snapshot
+            version: 0,
+            ..Default::default()
+        })
+    }
+}
+
+/// Deserializes an account whose layout only appended fields since it was written, reading
+/// the bytes it lacks as zeros.
+fn deserialize_padded<T: AccountDeserialize>(data: &[u8], space: usize) -> Result<T> {
+    let mut padded = data.to_vec();
+    if padded.len() < space {
+        padded.resize(space, 0);
+    }
+    T::try_deserialize(&mut &padded[..])
+}
+
+/// Deserializes a version 0 layout after checking the discriminator of the account type it was stored as.
+fn deserialize_v0<T: AnchorDeserialize>(data: &[u8], discriminator: &[u8]) -> Result<T> {
+    require!(
+        data.len() >= discriminator.len() && &data[..discriminator.len()] == discriminator,
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    let mut body = &data[discriminator.len()..];
+    Ok(T::deserialize(&mut body).map_err(|_| ErrorCode::AccountDidNotDeserialize)?)
+}
+
+// Reason code recorded in AuctionCancelled
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CancelReason {
+    InvalidParameters, // The round was started with wrong settings
+    Abuse, // The round is under attack (spam, manipulation)
+    Other,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    ConfigAdmin, // Auction settings and the bidding mint allowlist
+    Pauser, // pause_auctions / resume_auctions
+    PriceUpdater, // Reference prices of allowlisted mints
+    Treasurer, // withdraw_token
+    Moderator, // Content moderation of winning resource values
+}
+
+impl Role {
+    pub fn mask(self) -> u8 {
+        1 << (self as u8)
+    }
+}
+
+// Roles granted to one signer in a house. Role-gated instructions take it as their optional
+// `admin_roles` account, which is omitted when the signer is the config authority.
+#[account]
+#[derive(Default)]
+pub struct AdminRoles {
+    pub holder: Pubkey,
+    pub roles: u8, // Bitmask of Role::mask() values
+    pub bump: u8,
+}
+
+impl AdminRoles {
+    // Calculate space:
+    // 8 (discriminator)
+    // 32 (holder)
+    // 1 (roles)
+    // 1 (bump)
+    // = 8 + 32 + 1 + 1 = 42 bytes.
+    pub const SPACE: usize = 8 + 32 + 1 + 1;
+
+    pub fn has(&self, role: Role) -> bool {
+        self.roles & role.mask() != 0
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct RefundClaim {
+    pub bidder: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64, // Outbid amounts not yet claimed, accumulated across auctions
+    pub bump: u8,
+    pub rent_payer: Pubkey, // Funded the account; claim_refund returns the rent to it
+}
+
+impl RefundClaim {
+    // Calculate space:
+    // 8 (discriminator)
+    // 32 (bidder)
+    // 32 (mint)
+    // 8 (amount)
+    // 1 (bump)
+    // 32 (rent_payer)
+    // = 8 + 32 + 32 + 8 + 1 + 32 = 113 bytes.
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 1 + 32;
+}
+
+#[account]
+#[derive(Default)]
+pub struct RefundLedger {
+    pub mint: Pubkey,
+    pub pending_amount: u64, // Sum of unclaimed refunds escrowed in this mint's vault
+    pub bump: u8,
+    pub rent_payer: Pubkey, // Funded the account; close_refund_ledger returns the rent to it
+}
+
+impl RefundLedger {
+    // Calculate space:
+    // 8 (discriminator)
+    // 32 (mint)
+    // 8 (pending_amount)
+    // 1 (bump)
+    // 32 (rent_payer)
+    // = 8 + 32 + 8 + 1 + 32 = 81 bytes.
+    pub const SPACE: usize = 8 + 32 + 8 + 1 + 32;
+}
+
+#[account]
+#[derive(Default)]
+pub struct AllowedMint {
+    pub mint: Pubkey,
+    pub price_usd: u64, // Reference price of one whole token in USD with 8 decimals
+    pub decimals: u8, // Decimals of the mint, used to normalize bid amounts
+    pub vault_bump: u8, // Bump for this mint's program_token_account PDA
+    pub bump: u8,
+    pub rent_payer: Pubkey, // Funded the account; remove_allowed_mint returns the rent to it
+}
+
+impl AllowedMint {
+    // Calculate space:
+    // 8 (discriminator)
+    // 32 (mint)
+    // 8 (price_usd)
+    // 1 (decimals)
+    // 1 (vault_bump)
+    // 1 (bump)
+    // 32 (rent_payer)
+    // = 8 + 32 + 8 + 1 + 1 + 1 + 32 = 83 bytes. Add some buffer.
+    pub const SPACE: usize = 8 + 32 + 8 + 1 + 1 + 1 + 32 + 32 /* buffer */;
+
+    /// Value of `amount` base units in USD with 8 decimals, normalized by the mint's decimals.
+    pub fn usd_value(&self, amount: u64) -> Result<u64> {
+        usd_value(amount, self.price_usd, self.decimals)
+    }
+}
+
+// Compact record of the historical records closed by close_historical_auction_data
+#[account]
+#[derive(Default)]
+pub struct HistorySummary {
+    pub closed_count: u64, // Records are closed in auction ID order, so this is also the last closed ID
+    pub winners_count: u64,
+    pub reserve_not_met_count: u64,
+    pub cancelled_count: u64,
+    pub vetoed_count: u64,
+    pub chain_hash: [u8; 32], // hash(previous chain_hash || hash(record)) over every closed record
+    pub last_closed_end_timestamp: i64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; RESERVED_SPACE],
+}
+
+impl HistorySummary {
+    // Calculate space:
+    // 8 (discriminator)
+    // 8 (closed_count)
+    // 8 (winners_count)
+    // 8 (reserve_not_met_count)
+    // 8 (cancelled_count)
+    // 8 (vetoed_count)
+    // 32 (chain_hash)
+    // 8 (last_closed_end_timestamp)
+    // 1 (bump)
+    // 1 (version)
+    // 32 (reserved)
+    // = 8 + 8 + 8 + 8 + 8 + 8 + 32 + 8 + 1 + 1 + 32 = 122 bytes.
+    pub const SPACE: usize = 8 + 8 + 8 + 8 + 8 + 8 + 32 + 8 + 1 + 1 + RESERVED_SPACE;
+    pub const VERSION: u8 = 1;
+}
+
+#[account]
+#[derive(Default)]
+pub struct SolEscrow {
+    pub bump: u8,
+}
+
+impl SolEscrow {
+    // Calculate space:
+    // 8 (discriminator)
+    // 1 (bump)
+    // = 8 + 1 = 9 bytes.
+    pub const SPACE: usize = 8 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SplitRecipient {
+    pub wallet: Pubkey, // Receives lamports directly, tokens in its associated token account
+    pub bps: u16, // Share of the proceeds in basis points
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SplitPayout {
+    pub recipient: Pubkey,
+    pub amount: u64, // In base units of the winning mint
+    pub paid_to_treasury: bool, // The recipient's account could not take the payment, so the treasury got it
+}
+
+// How the proceeds of each finalized auction are shared out; one per house
+#[account]
+#[derive(Default)]
+pub struct RevenueSplit {
+    pub recipients: Vec<SplitRecipient>, // Max MAX_SPLIT_RECIPIENTS, shares sum to BPS_DENOMINATOR
+    pub bump: u8,
+    pub version: u8, // Layout version, RevenueSplit::VERSION
+    pub reserved: [u8; RESERVED_SPACE],
+}
+
+impl RevenueSplit {
+    // Calculate space:
+    // 8 (discriminator)
+    // 4 + MAX_SPLIT_RECIPIENTS * (32 + 2) (recipients)
+    // 1 (bump)
+    // 1 (version)
+    // 32 (reserved)
+    // = 8 + (4 + 5 * 34) + 1 + 1 + 32 = 216 bytes.
+    pub const SPACE: usize = 8 + (4 + MAX_SPLIT_RECIPIENTS * (32 + 2)) + 1 + 1 + RESERVED_SPACE;
+    pub const VERSION: u8 = 1;
+
+    /// Each recipient's share of `proceeds`, rounded down; the last recipient also receives the remainder.
+    pub fn payouts(&self, proceeds: u64) -> Result<Vec<SplitPayout>> {
+        let mut remaining = proceeds;
+        let mut payouts = Vec::with_capacity(self.recipients.len());
+        for (index, recipient) in self.recipients.iter().enumerate() {
+            let amount = if index + 1 == self.recipients.len() {
+                remaining
+            } else {
+                ((proceeds as u128) * (recipient.bps as u128) / BPS_DENOMINATOR as u128) as u64
+            };
+            remaining = remaining.checked_sub(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+            payouts.push(SplitPayout { recipient: recipient.wallet, amount, paid_to_treasury: false });
+        }
+        Ok(payouts)
+    }
+}
+
+// Returned by get_auction_status
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct AuctionStatus {
+    pub auction_id: u64,
+    pub start_timestamp: i64, // Bids are rejected before this time
+    pub time_remaining: u64, // Seconds until bidding closes, 0 once the auction has ended
+    pub end_timestamp: i64,
+    pub highest_bidder: Pubkey,
+    pub bidding_token_mint: Pubkey, // Default bidding mint of this round
+    pub highest_bid_mint: Pubkey,
+    pub highest_bid_amount: u64,
+    pub highest_bid_usd_value: u64,
+    pub min_next_bid_usd_value: u64, // USD value (8 decimals) the next bid must reach
+    pub is_finalizable: bool,
+    pub auctions_paused: bool,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    house_id: u64,
+    resource_name: String, // Used for seed derivation if needed, and validation
+    default_resource_value: String,
+    auction_duration: u64
+)]
+pub struct InitializeAuction<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = AuctionConfig::SPACE,
+        seeds = [b"auction_config".as_ref(), authority.key().as_ref(), &house_id.to_le_bytes()], // One config per house
+        bump
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = AuctionState::SPACE,
+        seeds = [
+            b"active_auction".as_ref(),
+            auction_config.key().as_ref(),
+            &1u64.to_le_bytes() // Seed with initial auction ID (1)
+        ],
+        bump
+    )]
+    pub auction_state: Account<'info, AuctionState>,
+
+    #[account(
+        init,
+        payer = payer,
+        token::mint = bidding_token_mint,
+        token::authority = program_token_account, // The program_token_account PDA is its own authority
+        seeds = [b"program_token_account".as_ref(), auction_config.key().as_ref(), bidding_token_mint.key().as_ref()], // One vault per mint
+        bump
+    )]
+    pub program_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = AllowedMint::SPACE,
+        seeds = [b"allowed_mint".as_ref(), auction_config.key().as_ref(), bidding_token_mint.key().as_ref()],
+        bump
+    )]
+    pub allowed_mint: Account<'info, AllowedMint>,
+
+    pub bidding_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>, // The authority for the auction settings
+
+    #[account(mut)]
+    pub payer: Signer<'info>, // Payer for account initializations
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub rent: Sysvar<'info, Rent>, // Rent sysvar for account initialization
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, resource_value: String)]
+pub struct PlaceBid<'info> {
+    #[account(
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump, // auction_config bump is loaded from the account itself if already initialized
+    )]
+    pub auction_config: Box<Account<'info, AuctionConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"active_auction".as_ref(),
+            auction_config.key().as_ref(),
+            &auction_config.current_auction_id.to_le_bytes()
+        ],
+        bump, // auction_state bump is loaded from the account itself
+        constraint = auction_state.id == auction_config.current_auction_id @ AuctionError::AuctionNotActiveOrNotEnded
+    )]
+    pub auction_state: Box<Account<'info, AuctionState>>,
+
+    // The four token accounts below are omitted in native SOL mode. Optional accounts cannot derive
+    // seeds from each other, so place_bid checks that they all refer to the same mint.
+    pub bid_mint: Option<Box<InterfaceAccount<'info, Mint>>>, // Mint the bid is placed in, must be allowlisted
+
+    #[account(
+        seeds = [b"allowed_mint".as_ref(), auction_config.key().as_ref(), allowed_mint.mint.as_ref()],
+        bump = allowed_mint.bump
+    )]
+    pub allowed_mint: Option<Account<'info, AllowedMint>>,
+
+    #[account(
+        mut,
+        seeds = [b"program_token_account".as_ref(), auction_config.key().as_ref(), program_token_account.mint.as_ref()],
+        bump
+    )]
+    pub program_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(mut, token::mint = bid_mint)]
+    pub bidder_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>, // Bidder's token account (from where tokens are transferred)
+
+    // Lamport escrow, only used in native SOL mode
+    #[account(
+        init_if_needed,
+        payer = bidder_authority,
+        space = SolEscrow::SPACE,
+        seeds = [b"sol_escrow".as_ref(), auction_config.key().as_ref()],
+        bump
+    )]
+    pub sol_escrow: Option<Account<'info, SolEscrow>>,
+
+    // Refund claim credited with the outbid amount. Omitted on the first bid of an auction and on top-ups.
+    #[account(
+        init_if_needed,
+        payer = bidder_authority,
+        space = RefundClaim::SPACE,
+        seeds = [
+            b"refund_claim".as_ref(),
+            auction_config.key().as_ref(),
+            auction_state.highest_bidder.as_ref(),
+            auction_state.highest_bid_mint.as_ref()
+        ],
+        bump
+    )]
+    pub previous_bidder_refund_claim: Option<Account<'info, RefundClaim>>,
+
+    // Tracks refunds owed from the outbid mint's vault. Omitted on the first bid of an auction and on top-ups.
+    #[account(
+        init_if_needed,
+        payer = bidder_authority,
+        space = RefundLedger::SPACE,
+        seeds = [b"refund_ledger".as_ref(), auction_config.key().as_ref(), auction_state.highest_bid_mint.as_ref()],
+        bump
+    )]
+    pub previous_bid_refund_ledger: Option<Account<'info, RefundLedger>>,
+
+
+    #[account(mut)]
+    pub bidder_authority: Signer<'info>, // The bidder
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRefund<'info> {
+    #[account(
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"refund_claim".as_ref(), auction_config.key().as_ref(), bidder.key().as_ref(), refund_claim.mint.as_ref()],
+        bump = refund_claim.bump,
+        close = rent_payer
+    )]
+    pub refund_claim: Account<'info, RefundClaim>,
+
+    /// CHECK: Whoever funded the refund claim; only receives its rent back
+    #[account(mut, address = refund_claim.rent_payer)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"refund_ledger".as_ref(), auction_config.key().as_ref(), refund_claim.mint.as_ref()],
+        bump = refund_ledger.bump
+    )]
+    pub refund_ledger: Account<'info, RefundLedger>,
+
+    // The three token accounts below are omitted for refunds of native SOL bids
+    #[account(
+        mut,
+        seeds = [b"program_token_account".as_ref(), auction_config.key().as_ref(), refund_claim.mint.as_ref()],
+        bump
+    )]
+    pub program_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = refund_claim.mint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    // Any token account of the refunded mint chosen by the bidder
+    #[account(mut, token::mint = refund_claim.mint)]
+    pub destination_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Lamport escrow, only required for refunds of native SOL bids (paid to the bidder's wallet)
+    #[account(mut, seeds = [b"sol_escrow".as_ref(), auction_config.key().as_ref()], bump = sol_escrow.bump)]
+    pub sol_escrow: Option<Account<'info, SolEscrow>>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRefundLedger<'info> {
+    #[account(
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"refund_ledger".as_ref(), auction_config.key().as_ref(), refund_ledger.mint.as_ref()],
+        bump = refund_ledger.bump,
+        close = rent_payer
+    )]
+    pub refund_ledger: Account<'info, RefundLedger>,
+
+    /// CHECK: Whoever funded the refund ledger; only receives its rent back
+    #[account(mut, address = refund_ledger.rent_payer)]
+    pub rent_payer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeAuction<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump
+    )]
+    pub auction_config: Box<Account<'info, AuctionConfig>>,
+
+    #[account(
+        mut, // Mutable because it will be closed
+        seeds = [
+            b"active_auction".as_ref(),
+            auction_config.key().as_ref(),
+            &auction_config.current_auction_id.to_le_bytes()
+        ],
+        bump,
+        constraint = current_auction_state.id == auction_config.current_auction_id @ AuctionError::AuctionNotActiveOrNotEnded,
+        close = payer // Close the account and return rent to payer
+    )]
+    pub current_auction_state: Box<Account<'info, AuctionState>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = HistoricalAuctionData::SPACE,
+        seeds = [
+            b"historical_auction".as_ref(),
+            auction_config.key().as_ref(),
+            &auction_config.current_auction_id.to_le_bytes() // Use current_auction_id BEFORE it's incremented
+        ],
+        bump
+    )]
+    pub historical_auction_data: Box<Account<'info, HistoricalAuctionData>>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = AuctionState::SPACE,
+        seeds = [
+            b"active_auction".as_ref(),
+            auction_config.key().as_ref(),
+            &(auction_config.current_auction_id + 1).to_le_bytes() // Simpler expression for seed derivation
+        ],
+        bump
+    )]
+    pub new_auction_state: Option<Box<Account<'info, AuctionState>>>, // Must be omitted while auctions are paused
+
+    // Vault of the mint the winning bid was placed in. Not needed when the auction received no bids.
+    #[account(
+        mut,
+        seeds = [b"program_token_account".as_ref(), auction_config.key().as_ref(), current_auction_state.highest_bid_mint.as_ref()],
+        bump
+    )]
+    pub program_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    // Mint the winning bid was placed in, required alongside its vault for transfer_checked; burns reduce its supply
+    #[account(mut, address = current_auction_state.highest_bid_mint)]
+    pub winning_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// CHECK: Owner of the proceeds; receives lamport proceeds directly. Its address is verified against the configured treasury.
+    #[account(mut, address = auction_config.treasury @ AuctionError::InvalidTreasury)]
+    pub treasury: UncheckedAccount<'info>,
+
+    // Finalizing is permissionless, so token proceeds can only go to the treasury's associated token
+    // account for the winning mint, created on first use. Omitted when the winning bid was placed in native SOL.
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = winning_mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    // Lamport escrow, only required when the winning bid was placed in native SOL
+    #[account(mut, seeds = [b"sol_escrow".as_ref(), auction_config.key().as_ref()], bump = sol_escrow.bump)]
+    pub sol_escrow: Option<Account<'info, SolEscrow>>,
+
+    // Required while a revenue split is configured; the treasury token account can then be omitted
+    // unless a recipient's share falls back to the treasury
+    #[account(seeds = [b"revenue_split".as_ref(), auction_config.key().as_ref()], bump = revenue_split.bump)]
+    pub revenue_split: Option<Box<Account<'info, RevenueSplit>>>,
+
+    // Refund claim of the highest bidder, only required when the reserve price was not met
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = RefundClaim::SPACE,
+        seeds = [
+            b"refund_claim".as_ref(),
+            auction_config.key().as_ref(),
+            current_auction_state.highest_bidder.as_ref(),
+            current_auction_state.highest_bid_mint.as_ref()
+        ],
+        bump
+    )]
+    pub highest_bidder_refund_claim: Option<Account<'info, RefundClaim>>,
+
+    // Refund ledger of the highest bid mint, only required when the reserve price was not met
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = RefundLedger::SPACE,
+        seeds = [b"refund_ledger".as_ref(), auction_config.key().as_ref(), current_auction_state.highest_bid_mint.as_ref()],
+        bump
+    )]
+    pub highest_bid_refund_ledger: Option<Account<'info, RefundLedger>>,
+
+    // Receives the cranker's share of a winning token bid; omit to skip it, and when there is no token bid
+    #[account(mut, token::mint = current_auction_state.highest_bid_mint, token::authority = payer)]
+    pub cranker_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>, // Anyone can finalize (crank); pays for new account initializations and receives the reward
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAuction<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        constraint = auction_config.has_role(&authority.key(), admin_roles.as_deref(), Role::ConfigAdmin) @ AuctionError::MissingRole
+    )]
+    pub auction_config: Box<Account<'info, AuctionConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"active_auction".as_ref(),
+            auction_config.key().as_ref(),
+            &auction_config.current_auction_id.to_le_bytes()
+        ],
+        bump,
+        constraint = current_auction_state.id == auction_config.current_auction_id @ AuctionError::AuctionNotActiveOrNotEnded,
+        close = treasury
+    )]
+    pub current_auction_state: Box<Account<'info, AuctionState>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = HistoricalAuctionData::SPACE,
+        seeds = [
+            b"historical_auction".as_ref(),
+            auction_config.key().as_ref(),
+            &auction_config.current_auction_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub historical_auction_data: Box<Account<'info, HistoricalAuctionData>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = AuctionState::SPACE,
+        seeds = [
+            b"active_auction".as_ref(),
+            auction_config.key().as_ref(),
+            &(auction_config.current_auction_id + 1).to_le_bytes()
+        ],
+        bump
+    )]
+    pub new_auction_state: Option<Box<Account<'info, AuctionState>>>, // Omit when pausing or while paused
+
+    // Refund claim of the highest bidder, only required when the round has a bid
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = RefundClaim::SPACE,
+        seeds = [
+            b"refund_claim".as_ref(),
+            auction_config.key().as_ref(),
+            current_auction_state.highest_bidder.as_ref(),
+            current_auction_state.highest_bid_mint.as_ref()
+        ],
+        bump
+    )]
+    pub highest_bidder_refund_claim: Option<Account<'info, RefundClaim>>,
+
+    // Refund ledger of the highest bid mint, only required when the round has a bid
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = RefundLedger::SPACE,
+        seeds = [b"refund_ledger".as_ref(), auction_config.key().as_ref(), current_auction_state.highest_bid_mint.as_ref()],
+        bump
+    )]
+    pub highest_bid_refund_ledger: Option<Account<'info, RefundLedger>>,
+
+    /// CHECK: Only receives the rent of the cancelled auction state. Its address is verified against the configured treasury.
+    #[account(mut, address = auction_config.treasury @ AuctionError::InvalidTreasury)]
+    pub treasury: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), authority.key().as_ref()], bump = admin_roles.bump)]
+    pub admin_roles: Option<Account<'info, AdminRoles>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetAuctionStatus<'info> {
+    #[account(
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+
+    // Omitted when the current auction was finalized while paused
+    #[account(
+        seeds = [
+            b"active_auction".as_ref(),
+            auction_config.key().as_ref(),
+            &auction_config.current_auction_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub auction_state: Option<Account<'info, AuctionState>>,
+}
+
+#[derive(Accounts)]
+pub struct PauseAuctions<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        constraint = auction_config.has_role(&authority.key(), admin_roles.as_deref(), Role::Pauser) @ AuctionError::MissingRole
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), authority.key().as_ref()], bump = admin_roles.bump)]
+    pub admin_roles: Option<Account<'info, AdminRoles>>,
+}
+
+#[derive(Accounts)]
+pub struct ResumeAuctions<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        constraint = auction_config.has_role(&authority.key(), admin_roles.as_deref(), Role::Pauser) @ AuctionError::MissingRole
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+
+    // Only required if the current auction was finalized while paused
+    #[account(
         init,
         payer = payer,
-        space = AuctionConfig::SPACE,
-        seeds = [b"auction_config".as_ref()], // Single global config
+        space = AuctionState::SPACE,
+        seeds = [
+            b"active_auction".as_ref(),
+            auction_config.key().as_ref(),
+            &(auction_config.current_auction_id + 1).to_le_bytes()
+        ],
+        bump
+    )]
+    pub new_auction_state: Option<Account<'info, AuctionState>>,
+
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), authority.key().as_ref()], bump = admin_roles.bump)]
+    pub admin_roles: Option<Account<'info, AdminRoles>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// --- Accounts for Configuration Instructions ---
+
+#[derive(Accounts)]
+pub struct SetBiddingToken<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump, // Anchor infers bump from the initialized auction_config account
+        constraint = auction_config.has_role(&authority.key(), admin_roles.as_deref(), Role::ConfigAdmin) @ AuctionError::MissingRole
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), authority.key().as_ref()], bump = admin_roles.bump)]
+    pub admin_roles: Option<Account<'info, AdminRoles>>,
+
+    // Omitted when the current auction was finalized while paused
+    #[account(
+        seeds = [
+            b"active_auction".as_ref(),
+            auction_config.key().as_ref(),
+            &auction_config.current_auction_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub auction_state: Option<Account<'info, AuctionState>>,
+
+    pub new_bidding_token_mint_account: InterfaceAccount<'info, Mint>,
+
+    // Vault of the new mint, created here unless the mint was already allowlisted or used before
+    #[account(
+        init_if_needed,
+        payer = payer,
+        token::mint = new_bidding_token_mint_account,
+        token::authority = program_token_account,
+        seeds = [b"program_token_account".as_ref(), auction_config.key().as_ref(), new_bidding_token_mint_account.key().as_ref()],
+        bump
+    )]
+    pub program_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // Allowlist entry of the new mint, created here unless the mint is already allowlisted
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = AllowedMint::SPACE,
+        seeds = [b"allowed_mint".as_ref(), auction_config.key().as_ref(), new_bidding_token_mint_account.key().as_ref()],
+        bump
+    )]
+    pub allowed_mint: Account<'info, AllowedMint>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct SetAuctionDuration<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        constraint = auction_config.has_role(&authority.key(), admin_roles.as_deref(), Role::ConfigAdmin) @ AuctionError::MissingRole
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), authority.key().as_ref()], bump = admin_roles.bump)]
+    pub admin_roles: Option<Account<'info, AdminRoles>>,
+}
+
+#[derive(Accounts)]
+#[instruction(new_treasury: Pubkey)]
+pub struct SetTreasury<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        has_one = authority @ AuctionError::InvalidAuthority
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+
+    /// CHECK: Only its balance is read, to make sure it can take lamport proceeds
+    #[account(address = new_treasury @ AuctionError::InvalidTreasury)]
+    pub treasury: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRevenueSplit<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        has_one = authority @ AuctionError::InvalidAuthority
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = RevenueSplit::SPACE,
+        seeds = [b"revenue_split".as_ref(), auction_config.key().as_ref()],
+        bump
+    )]
+    pub revenue_split: Account<'info, RevenueSplit>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>, // Pays for the split table on first use
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        has_one = authority @ AuctionError::InvalidAuthority
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        has_one = pending_authority @ AuctionError::NotPendingAuthority
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+    pub pending_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAuthorityTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        has_one = authority @ AuctionError::InvalidAuthority
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(holder: Pubkey, role: Role)]
+pub struct GrantRole<'info> {
+    #[account(
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        has_one = authority @ AuctionError::InvalidAuthority
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = AdminRoles::SPACE,
+        seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), holder.as_ref()],
         bump
     )]
+    pub admin_roles: Account<'info, AdminRoles>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(holder: Pubkey, role: Role)]
+pub struct RevokeRole<'info> {
+    #[account(
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        has_one = authority @ AuctionError::InvalidAuthority
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), holder.as_ref()],
+        bump = admin_roles.bump
+    )]
+    pub admin_roles: Account<'info, AdminRoles>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCrankerReward<'info> {
+    // Authority only: a share of every winning bid is paid out, so role holders cannot change it
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        has_one = authority @ AuctionError::InvalidAuthority
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetVetoWindow<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        constraint = auction_config.has_role(&authority.key(), admin_roles.as_deref(), Role::ConfigAdmin) @ AuctionError::MissingRole
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), authority.key().as_ref()], bump = admin_roles.bump)]
+    pub admin_roles: Option<Account<'info, AdminRoles>>,
+}
+
+#[derive(Accounts)]
+pub struct SetRoundCooldown<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        constraint = auction_config.has_role(&authority.key(), admin_roles.as_deref(), Role::ConfigAdmin) @ AuctionError::MissingRole
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), authority.key().as_ref()], bump = admin_roles.bump)]
+    pub admin_roles: Option<Account<'info, AdminRoles>>,
+}
+
+#[derive(Accounts)]
+pub struct SetHistoryRetention<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        constraint = auction_config.has_role(&authority.key(), admin_roles.as_deref(), Role::ConfigAdmin) @ AuctionError::MissingRole
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), authority.key().as_ref()], bump = admin_roles.bump)]
+    pub admin_roles: Option<Account<'info, AdminRoles>>,
+}
+
+#[derive(Accounts)]
+pub struct SetAntiSniping<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        constraint = auction_config.has_role(&authority.key(), admin_roles.as_deref(), Role::ConfigAdmin) @ AuctionError::MissingRole
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), authority.key().as_ref()], bump = admin_roles.bump)]
+    pub admin_roles: Option<Account<'info, AdminRoles>>,
+}
+
+#[derive(Accounts)]
+pub struct SetReservePrice<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        constraint = auction_config.has_role(&authority.key(), admin_roles.as_deref(), Role::ConfigAdmin) @ AuctionError::MissingRole
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), authority.key().as_ref()], bump = admin_roles.bump)]
+    pub admin_roles: Option<Account<'info, AdminRoles>>,
+}
+
+#[derive(Accounts)]
+pub struct SetBurnBps<'info> {
+    // Authority only: the burn share is taken out of the treasury's proceeds
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        has_one = authority @ AuctionError::InvalidAuthority
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetMinBidIncreaseBps<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        constraint = auction_config.has_role(&authority.key(), admin_roles.as_deref(), Role::ConfigAdmin) @ AuctionError::MissingRole
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), authority.key().as_ref()], bump = admin_roles.bump)]
+    pub admin_roles: Option<Account<'info, AdminRoles>>,
+}
+
+#[derive(Accounts)]
+pub struct SetNativeSolBidding<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        constraint = auction_config.has_role(&authority.key(), admin_roles.as_deref(), Role::ConfigAdmin) @ AuctionError::MissingRole
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), authority.key().as_ref()], bump = admin_roles.bump)]
+    pub admin_roles: Option<Account<'info, AdminRoles>>,
+}
+
+#[derive(Accounts)]
+#[instruction(new_value: String)] // The instruction argument is passed for context/logging
+pub struct SetDefaultResourceValue<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        constraint = auction_config.has_role(&authority.key(), admin_roles.as_deref(), Role::ConfigAdmin) @ AuctionError::MissingRole
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), authority.key().as_ref()], bump = admin_roles.bump)]
+    pub admin_roles: Option<Account<'info, AdminRoles>>,
+}
+
+#[derive(Accounts)]
+#[instruction(new_name: String)] // The instruction argument is passed for context/logging
+pub struct SetResourceName<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        constraint = auction_config.has_role(&authority.key(), admin_roles.as_deref(), Role::ConfigAdmin) @ AuctionError::MissingRole
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), authority.key().as_ref()], bump = admin_roles.bump)]
+    pub admin_roles: Option<Account<'info, AdminRoles>>,
+}
+
+// --- Accounts for Bidding Mint Allowlist Instructions ---
+
+#[derive(Accounts)]
+pub struct AddAllowedMint<'info> {
+    #[account(
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        constraint = auction_config.has_role(&authority.key(), admin_roles.as_deref(), Role::ConfigAdmin) @ AuctionError::MissingRole
+    )]
     pub auction_config: Account<'info, AuctionConfig>,
 
     #[account(
         init,
         payer = payer,
-        space = AuctionState::SPACE,
-        seeds = [
-            b"active_auction".as_ref(),
-            &1u64.to_le_bytes() // Seed with initial auction ID (1)
-        ],
+        space = AllowedMint::SPACE,
+        seeds = [b"allowed_mint".as_ref(), auction_config.key().as_ref(), mint.key().as_ref()],
         bump
     )]
-    pub auction_state: Account<'info, AuctionState>,
+    pub allowed_mint: Account<'info, AllowedMint>,
 
+    // Created on first allowlisting; reused if the mint was allowed before or is the initial bidding mint
     #[account(
-        init,
+        init_if_needed,
         payer = payer,
-        token::mint = bidding_token_mint,
-        token::authority = program_token_account, // The program_token_account PDA is its own authority
-        seeds = [b"program_token_account".as_ref()],
+        token::mint = mint,
+        token::authority = program_token_account,
+        seeds = [b"program_token_account".as_ref(), auction_config.key().as_ref(), mint.key().as_ref()],
         bump
     )]
-    pub program_token_account: Account<'info, TokenAccount>,
+    pub program_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    pub bidding_token_mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
 
-    #[account(mut)]
-    pub authority: Signer<'info>, // The authority for the auction settings
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), authority.key().as_ref()], bump = admin_roles.bump)]
+    pub admin_roles: Option<Account<'info, AdminRoles>>,
 
     #[account(mut)]
-    pub payer: Signer<'info>, // Payer for account initializations
+    pub payer: Signer<'info>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub rent: Sysvar<'info, Rent>, // Rent sysvar for account initialization
+    pub token_program: Interface<'info, TokenInterface>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-#[instruction(amount: u64, resource_value: String)]
-pub struct PlaceBid<'info> {
+pub struct RemoveAllowedMint<'info> {
     #[account(
-        seeds = [b"auction_config".as_ref()],
-        bump, // auction_config bump is loaded from the account itself if already initialized
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        constraint = auction_config.has_role(&authority.key(), admin_roles.as_deref(), Role::ConfigAdmin) @ AuctionError::MissingRole
     )]
     pub auction_config: Account<'info, AuctionConfig>,
 
+    // Omitted when the current auction was finalized while paused
     #[account(
-        mut,
         seeds = [
             b"active_auction".as_ref(),
+            auction_config.key().as_ref(),
             &auction_config.current_auction_id.to_le_bytes()
         ],
-        bump, // auction_state bump is loaded from the account itself
-        constraint = auction_state.id == auction_config.current_auction_id @ AuctionError::AuctionNotActiveOrNotEnded
+        bump
     )]
-    pub auction_state: Account<'info, AuctionState>,
+    pub auction_state: Option<Account<'info, AuctionState>>,
 
     #[account(
         mut,
-        seeds = [b"program_token_account".as_ref()],
-        bump = auction_config.program_token_account_bump
+        seeds = [b"allowed_mint".as_ref(), auction_config.key().as_ref(), allowed_mint.mint.as_ref()],
+        bump = allowed_mint.bump,
+        close = rent_payer
     )]
-    pub program_token_account: Account<'info, TokenAccount>,
+    pub allowed_mint: Account<'info, AllowedMint>,
 
-    #[account(mut)]
-    pub bidder_token_account: Account<'info, TokenAccount>, // Bidder's token account (from where tokens are transferred)
+    /// CHECK: Whoever funded the allowlist entry; only receives its rent back
+    #[account(mut, address = allowed_mint.rent_payer)]
+    pub rent_payer: UncheckedAccount<'info>,
 
-    /// CHECK: This account is used for refunding the previous bidder.
-    /// It's optional because the first bid won't have a previous bidder.
-    /// Constraints are checked in the instruction logic.
-    /// The client is responsible for providing this account if a refund is expected.
-    /// It must be a valid TokenAccount owned by the previous_bidder.
-    #[account(mut)]
-    pub previous_bidder_token_account: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), authority.key().as_ref()], bump = admin_roles.bump)]
+    pub admin_roles: Option<Account<'info, AdminRoles>>,
+}
 
+#[derive(Accounts)]
+pub struct SetTokenPrice<'info> {
+    #[account(
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        constraint = auction_config.has_role(&authority.key(), admin_roles.as_deref(), Role::PriceUpdater) @ AuctionError::MissingRole
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
 
-    #[account(mut)]
-    pub bidder_authority: Signer<'info>, // The bidder
+    // Omitted when the current auction was finalized while paused
+    #[account(
+        seeds = [
+            b"active_auction".as_ref(),
+            auction_config.key().as_ref(),
+            &auction_config.current_auction_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub auction_state: Option<Account<'info, AuctionState>>,
 
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+    #[account(
+        mut,
+        seeds = [b"allowed_mint".as_ref(), auction_config.key().as_ref(), allowed_mint.mint.as_ref()],
+        bump = allowed_mint.bump
+    )]
+    pub allowed_mint: Account<'info, AllowedMint>,
+
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), authority.key().as_ref()], bump = admin_roles.bump)]
+    pub admin_roles: Option<Account<'info, AdminRoles>>,
 }
 
 #[derive(Accounts)]
-pub struct FinalizeAuction<'info> {
-    #[account(mut, seeds = [b"auction_config".as_ref()], bump)]
+pub struct SetSolPrice<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        constraint = auction_config.has_role(&authority.key(), admin_roles.as_deref(), Role::PriceUpdater) @ AuctionError::MissingRole
+    )]
     pub auction_config: Account<'info, AuctionConfig>,
 
+    // Omitted when the current auction was finalized while paused
     #[account(
-        mut, // Mutable because it will be closed
         seeds = [
             b"active_auction".as_ref(),
+            auction_config.key().as_ref(),
             &auction_config.current_auction_id.to_le_bytes()
         ],
-        bump,
-        constraint = current_auction_state.id == auction_config.current_auction_id @ AuctionError::AuctionNotActiveOrNotEnded,
-        close = payer // Close the account and return rent to payer
+        bump
+    )]
+    pub auction_state: Option<Account<'info, AuctionState>>,
+
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), authority.key().as_ref()], bump = admin_roles.bump)]
+    pub admin_roles: Option<Account<'info, AdminRoles>>,
+}
+
+// --- Accounts for Treasury Instructions ---
+
+#[derive(Accounts)]
+#[instruction(vault_mint: Pubkey, amount: u64)]
+pub struct WithdrawToken<'info> {
+    #[account(
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        constraint = auction_config.has_role(&authority.key(), admin_roles.as_deref(), Role::Treasurer) @ AuctionError::MissingRole
     )]
-    pub current_auction_state: Account<'info, AuctionState>,
+    pub auction_config: Account<'info, AuctionConfig>,
 
+    // Omitted when the current auction was finalized while paused
     #[account(
-        init,
-        payer = payer,
-        space = HistoricalAuctionData::SPACE,
         seeds = [
-            b"historical_auction".as_ref(),
-            &auction_config.current_auction_id.to_le_bytes() // Use current_auction_id BEFORE it's incremented
+            b"active_auction".as_ref(),
+            auction_config.key().as_ref(),
+            &auction_config.current_auction_id.to_le_bytes()
         ],
         bump
     )]
+    pub auction_state: Option<Account<'info, AuctionState>>,
+
+    /// CHECK: Vault PDA of `vault_mint`, only used as the signing owner of `source_token_account`.
+    /// Its address is verified by the seeds constraint.
+    #[account(
+        seeds = [b"program_token_account".as_ref(), auction_config.key().as_ref(), vault_mint.as_ref()],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    // The vault itself, or any token account of another mint owned by the vault PDA
+    #[account(mut, token::authority = vault_authority)]
+    pub source_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = source_token_account.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, token::mint = source_token_account.mint)]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Refund ledger of the source mint, which may not exist yet if nobody was ever outbid in it.
+    /// Its address is verified by the seeds constraint and it is deserialized only if initialized.
+    #[account(
+        seeds = [b"refund_ledger".as_ref(), auction_config.key().as_ref(), source_token_account.mint.as_ref()],
+        bump
+    )]
+    pub refund_ledger: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), authority.key().as_ref()], bump = admin_roles.bump)]
+    pub admin_roles: Option<Account<'info, AdminRoles>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// --- Accounts for Moderation Instructions ---
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct VetoResourceValue<'info> {
+    #[account(
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        constraint = auction_config.has_role(&authority.key(), admin_roles.as_deref(), Role::Moderator) @ AuctionError::MissingRole
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"historical_auction".as_ref(), auction_config.key().as_ref(), &auction_id.to_le_bytes()],
+        bump
+    )]
     pub historical_auction_data: Account<'info, HistoricalAuctionData>,
-    
+
+    // The accounts below are only required when part of the winning bid is refunded
     #[account(
-        init,
-        payer = payer,
-        space = AuctionState::SPACE,
+        init_if_needed,
+        payer = authority,
+        space = RefundClaim::SPACE,
         seeds = [
-            b"active_auction".as_ref(),
-            &(auction_config.current_auction_id + 1).to_le_bytes() // Simpler expression for seed derivation
+            b"refund_claim".as_ref(),
+            auction_config.key().as_ref(),
+            historical_auction_data.winner.as_ref(),
+            historical_auction_data.winning_mint.as_ref()
         ],
         bump
     )]
-    pub new_auction_state: Account<'info, AuctionState>,
+    pub winner_refund_claim: Option<Account<'info, RefundClaim>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = RefundLedger::SPACE,
+        seeds = [b"refund_ledger".as_ref(), auction_config.key().as_ref(), historical_auction_data.winning_mint.as_ref()],
+        bump
+    )]
+    pub winning_mint_refund_ledger: Option<Account<'info, RefundLedger>>,
+
+    // Treasury funding the refund, who co-signs
+    #[account(mut, address = auction_config.treasury @ AuctionError::MissingTreasuryAuthority)]
+    pub treasury_authority: Option<Signer<'info>>,
+
+    // Token refunds: the treasury's token account, the winning mint and its vault
+    #[account(mut, token::mint = historical_auction_data.winning_mint, token::authority = auction_config.treasury)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = historical_auction_data.winning_mint)]
+    pub winning_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"program_token_account".as_ref(), auction_config.key().as_ref(), historical_auction_data.winning_mint.as_ref()],
+        bump
+    )]
+    pub program_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Native SOL refunds
+    #[account(mut, seeds = [b"sol_escrow".as_ref(), auction_config.key().as_ref()], bump = sol_escrow.bump)]
+    pub sol_escrow: Option<Account<'info, SolEscrow>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>, // The moderator; pays for the refund claim and ledger if they don't exist yet
+
+    #[account(seeds = [b"admin_roles".as_ref(), auction_config.key().as_ref(), authority.key().as_ref()], bump = admin_roles.bump)]
+    pub admin_roles: Option<Account<'info, AdminRoles>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+// --- Accounts for History Maintenance ---
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct CloseHistoricalAuctionData<'info> {
+    #[account(
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"historical_auction".as_ref(), auction_config.key().as_ref(), &auction_id.to_le_bytes()],
+        bump,
+        close = treasury
+    )]
+    pub historical_auction_data: Account<'info, HistoricalAuctionData>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = HistorySummary::SPACE,
+        seeds = [b"history_summary".as_ref(), auction_config.key().as_ref()],
+        bump
+    )]
+    pub history_summary: Account<'info, HistorySummary>,
+
+    /// CHECK: Only receives the reclaimed rent. Its address is verified against the configured treasury.
+    #[account(mut, address = auction_config.treasury @ AuctionError::InvalidTreasury)]
+    pub treasury: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>, // Anyone can close expired records; pays for the summary on first use
+
+    pub system_program: Program<'info, System>,
+}
+
+// --- Accounts for Migration Instructions ---
+
+#[derive(Accounts)]
+#[instruction(creator: Pubkey, house_id: u64)]
+pub struct MigrateAuctionConfig<'info> {
+    /// CHECK: May still have an older, shorter layout, so the handler deserializes it padded with zeros.
+    /// Its address is verified by the seeds constraint; discriminator and authority are checked by the handler.
+    #[account(mut, seeds = [b"auction_config".as_ref(), creator.as_ref(), &house_id.to_le_bytes()], bump, owner = crate::ID)]
+    pub auction_config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>, // Pays the rent for the extra space
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct MigrateAuctionState<'info> {
+    #[account(
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        has_one = authority @ AuctionError::InvalidAuthority
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+
+    /// CHECK: May still have an older, shorter layout, so the handler deserializes it padded with zeros.
+    /// Its address is verified by the seeds constraint; the discriminator is checked by the handler.
+    #[account(
+        mut,
+        seeds = [b"active_auction".as_ref(), auction_config.key().as_ref(), &auction_id.to_le_bytes()],
+        bump,
+        owner = crate::ID
+    )]
+    pub auction_state: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>, // Pays the rent for the extra space
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct MigrateHistoricalAuctionData<'info> {
+    #[account(
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        has_one = authority @ AuctionError::InvalidAuthority
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+
+    /// CHECK: May still have an older, shorter layout, so the handler deserializes it padded with zeros.
+    /// Its address is verified by the seeds constraint; the discriminator is checked by the handler.
+    #[account(
+        mut,
+        seeds = [b"historical_auction".as_ref(), auction_config.key().as_ref(), &auction_id.to_le_bytes()],
+        bump,
+        owner = crate::ID
+    )]
+    pub historical_auction_data: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>, // Pays the rent for the extra space
+
+    pub system_program: Program<'info, System>,
+}
+
+// --- Accounts for Legacy Seed Migration ---
+
+#[derive(Accounts)]
+#[instruction(house_id: u64)]
+pub struct MigrateLegacyConfig<'info> {
+    /// CHECK: Config of the first deployment, at the seed used before auction houses; the handler reads
+    /// it with AuctionConfig::from_v0 and checks its authority. Closed once moved.
+    #[account(mut, seeds = [b"auction_config".as_ref()], bump, owner = crate::ID)]
+    pub legacy_auction_config: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = AuctionConfig::SPACE,
+        seeds = [b"auction_config".as_ref(), authority.key().as_ref(), &house_id.to_le_bytes()],
+        bump
+    )]
+    pub auction_config: Account<'info, AuctionConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = AllowedMint::SPACE,
+        seeds = [b"allowed_mint".as_ref(), auction_config.key().as_ref(), bidding_token_mint.key().as_ref()],
+        bump
+    )]
+    pub allowed_mint: Account<'info, AllowedMint>,
 
+    // House vault of the bidding mint; migrate_legacy_vault fills it from the legacy vault
     #[account(
-        mut,
-        seeds = [b"program_token_account".as_ref()],
-        bump = auction_config.program_token_account_bump
+        init,
+        payer = authority,
+        token::mint = bidding_token_mint,
+        token::authority = program_token_account,
+        seeds = [b"program_token_account".as_ref(), auction_config.key().as_ref(), bidding_token_mint.key().as_ref()],
+        bump
     )]
-    pub program_token_account: Account<'info, TokenAccount>,
+    pub program_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(mut)] // Authority's token account to receive winnings
-    pub authority_token_account: Account<'info, TokenAccount>,
-    
-    // Ensure this is the actual authority of the auction_config
-    #[account(address = auction_config.authority @ AuctionError::InvalidAuthority)]
-    pub authority: Signer<'info>, 
+    pub bidding_token_mint: InterfaceAccount<'info, Mint>, // Checked against the legacy config by the handler
 
     #[account(mut)]
-    pub payer: Signer<'info>, // Payer for new account initializations
+    pub authority: Signer<'info>, // Authority of the legacy config; creates and owns the house
 
-    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub rent: Sysvar<'info, Rent>,
 }
 
-// --- Accounts for Configuration Instructions ---
-
 #[derive(Accounts)]
-pub struct SetBiddingToken<'info> {
+#[instruction(auction_id: u64)]
+pub struct MigrateLegacyAuctionState<'info> {
     #[account(
-        mut,
-        seeds = [b"auction_config".as_ref()],
-        bump, // Anchor infers bump from the initialized auction_config account
-        has_one = authority @ AuctionError::InvalidAuthority
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        has_one = authority @ AuctionError::InvalidAuthority,
+        constraint = auction_config.legacy_house @ AuctionError::NotLegacyHouse
     )]
     pub auction_config: Account<'info, AuctionConfig>,
+
+    /// CHECK: Auction state of the first deployment, at the pre-house seeds; read with
+    /// AuctionState::from_v0, which checks the discriminator. Closed once moved.
+    #[account(mut, seeds = [b"active_auction".as_ref(), &auction_id.to_le_bytes()], bump, owner = crate::ID)]
+    pub legacy_auction_state: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = AuctionState::SPACE,
+        seeds = [b"active_auction".as_ref(), auction_config.key().as_ref(), &auction_id.to_le_bytes()],
+        bump
+    )]
+    pub auction_state: Account<'info, AuctionState>,
+
+    // Entry of the config's bidding mint; prices the highest bid of a version 0 round that has one
+    #[account(
+        seeds = [b"allowed_mint".as_ref(), auction_config.key().as_ref(), auction_config.bidding_token_mint.as_ref()],
+        bump = allowed_mint.bump
+    )]
+    pub allowed_mint: Option<Account<'info, AllowedMint>>,
+
+    #[account(mut)]
     pub authority: Signer<'info>,
-    /// CHECK: This is the Pubkey of the new mint. The instruction does a basic check.
-    /// For full safety, the client should ensure this is a valid mint account, or this could be
-    /// changed to Account<'info, Mint> if the program needs to read data from the mint account itself.
-    pub new_bidding_token_mint_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct SetAuctionDuration<'info> {
+#[instruction(auction_id: u64)]
+pub struct MigrateLegacyHistoricalAuctionData<'info> {
     #[account(
-        mut,
-        seeds = [b"auction_config".as_ref()],
-        bump,
-        has_one = authority @ AuctionError::InvalidAuthority
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        has_one = authority @ AuctionError::InvalidAuthority,
+        constraint = auction_config.legacy_house @ AuctionError::NotLegacyHouse
     )]
     pub auction_config: Account<'info, AuctionConfig>,
+
+    /// CHECK: Historical record of the first deployment, at the pre-house seeds; read with
+    /// HistoricalAuctionData::from_v0, which checks the discriminator. Closed once moved.
+    #[account(mut, seeds = [b"historical_auction".as_ref(), &auction_id.to_le_bytes()], bump, owner = crate::ID)]
+    pub legacy_historical_auction_data: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = HistoricalAuctionData::SPACE,
+        seeds = [b"historical_auction".as_ref(), auction_config.key().as_ref(), &auction_id.to_le_bytes()],
+        bump
+    )]
+    pub historical_auction_data: Account<'info, HistoricalAuctionData>,
+
+    #[account(mut)]
     pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-#[instruction(new_value: String)] // The instruction argument is passed for context/logging
-pub struct SetDefaultResourceValue<'info> {
+pub struct MigrateLegacyVault<'info> {
     #[account(
-        mut,
-        seeds = [b"auction_config".as_ref()],
-        bump,
-        has_one = authority @ AuctionError::InvalidAuthority
+        seeds = [b"auction_config".as_ref(), auction_config.creator.as_ref(), &auction_config.house_id.to_le_bytes()],
+        bump = auction_config.bump,
+        has_one = authority @ AuctionError::InvalidAuthority,
+        constraint = auction_config.legacy_house @ AuctionError::NotLegacyHouse
     )]
     pub auction_config: Account<'info, AuctionConfig>,
-    pub authority: Signer<'info>,
-}
 
-#[derive(Accounts)]
-#[instruction(new_name: String)] // The instruction argument is passed for context/logging
-pub struct SetResourceName<'info> {
+    // The single vault of the first deployment, holding its bidding mint
+    #[account(mut, seeds = [b"program_token_account".as_ref()], bump, token::mint = mint)]
+    pub legacy_program_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // migrate_legacy_config allowlists the legacy bidding mint, which also creates its house vault
+    #[account(
+        seeds = [b"allowed_mint".as_ref(), auction_config.key().as_ref(), mint.key().as_ref()],
+        bump = allowed_mint.bump
+    )]
+    pub allowed_mint: Account<'info, AllowedMint>,
+
     #[account(
         mut,
-        seeds = [b"auction_config".as_ref()],
-        bump,
-        has_one = authority @ AuctionError::InvalidAuthority
+        seeds = [b"program_token_account".as_ref(), auction_config.key().as_ref(), mint.key().as_ref()],
+        bump = allowed_mint.vault_bump
     )]
-    pub auction_config: Account<'info, AuctionConfig>,
-    pub authority: Signer<'info>,
+    pub program_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>, // Receives the rent of the closed legacy vault
+
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 // Custom error codes will be defined here
@@ -561,39 +3919,694 @@ pub enum AuctionError {
     #[msg("Timestamp error.")]
     TimestampError,
     #[msg("Previous bidder token account not provided or invalid for refund.")]
-    MissingOrInvalidPreviousBidderTokenAccount,
+    MissingOrInvalidPreviousBidderTokenAccount, // Deprecated: refunds go to a RefundClaim; kept so later codes keep their numbers
     #[msg("Owner of previous bidder token account does not match highest bidder.")]
-    InvalidPreviousBidderTokenAccountOwner,
-    #[msg("Mint of previous bidder token account does not match bidding token mint.")]
+    InvalidPreviousBidderTokenAccountOwner, // Deprecated, as above
+    #[msg("Bid mint does not match the allowlisted mint.")]
     InvalidBiddingMint,
     #[msg("Auction has not actually ended according to its duration.")] // Specific for finalize_auction time check
     AuctionNotYetEnded, // Replaces the generic AuctionNotEnded for this context
     #[msg("The provided authority does not match the auction config authority.")]
     InvalidAuthority,
+    #[msg("Token price must be greater than zero.")]
+    InvalidTokenPrice,
+    #[msg("The mint is used by the active highest bid.")]
+    MintUsedByActiveBid,
+    #[msg("Vault for the winning bid mint not provided or invalid.")]
+    MissingOrInvalidBidVault,
+    #[msg("Bid does not exceed the highest bid by the minimum increase.")]
+    BidIncreaseTooLow,
+    #[msg("Minimum bid increase cannot exceed 10000 basis points.")]
+    MinBidIncreaseTooHigh,
+    #[msg("Auctions are paused.")]
+    AuctionsArePaused,
+    #[msg("Auctions are not paused.")]
+    AuctionsNotPaused,
+    #[msg("New auction state account is required to start the next auction.")]
+    MissingNewAuctionState,
+    #[msg("New auction state account must not be provided when no auction is started.")]
+    UnexpectedNewAuctionState,
+    #[msg("Current auction state account is required.")]
+    MissingAuctionState,
+    #[msg("Withdraw amount must be greater than zero.")]
+    InvalidWithdrawAmount,
+    #[msg("Locked amount exceeds the account balance.")]
+    LockedAmountExceedsBalance,
+    #[msg("Amount exceeds the withdrawable balance.")]
+    AmountExceedsWithdrawableBalance,
+    #[msg("Proceeds destination must be the treasury's associated token account for the winning mint.")]
+    InvalidProceedsDestination,
+    #[msg("Cranker reward exceeds the maximum share of the winning bid.")]
+    CrankerRewardTooHigh,
+    #[msg("Refund claim and refund ledger accounts of the previous bidder are required.")]
+    MissingRefundClaim,
+    #[msg("Refund claim accounts must not be provided when there is no previous bid.")]
+    UnexpectedRefundClaim,
+    #[msg("The refund ledger still has unclaimed refunds.")]
+    RefundLedgerNotEmpty,
+    #[msg("Pending authority cannot be the default public key.")]
+    InvalidPendingAuthority,
+    #[msg("There is no pending authority transfer.")]
+    NoPendingAuthority,
+    #[msg("Signer is not the pending authority.")]
+    NotPendingAuthority,
+    #[msg("Signer is neither the authority nor holds the required role.")]
+    MissingRole,
+    #[msg("An enabled soft close needs a non-zero max_total_extension.")]
+    InvalidAntiSnipingConfig,
+    #[msg("Bid is below the minimum opening bid.")]
+    BelowMinimumOpeningBid,
+    #[msg("Mint has a Token-2022 extension that is incompatible with escrow.")]
+    UnsupportedMintExtension,
+    #[msg("Token accounts of the bid mint are required unless bidding in native SOL.")]
+    MissingBidTokenAccounts,
+    #[msg("Token accounts must be omitted when bidding in native SOL.")]
+    UnexpectedBidTokenAccounts,
+    #[msg("The native SOL escrow account is required for lamport bids.")]
+    MissingSolEscrow,
+    #[msg("Payout would leave the SOL escrow below its rent-exempt minimum.")]
+    SolEscrowBelowRentExemption,
+    #[msg("Wrapped SOL cannot be allowlisted; enable native SOL bidding instead.")]
+    NativeMintNotAllowed,
+    #[msg("The auction has no winning resource value to veto.")]
+    NothingToVeto,
+    #[msg("The winning resource value was already vetoed.")]
+    AlreadyVetoed,
+    #[msg("The review window for vetoing this auction has closed.")]
+    VetoWindowClosed,
+    #[msg("Veto refund exceeds the winning bid.")]
+    VetoRefundTooHigh,
+    #[msg("The treasury must sign and fund a veto refund.")]
+    MissingTreasuryAuthority,
+    #[msg("Account is already on the current layout version.")]
+    AccountAlreadyMigrated,
+    #[msg("Historical records are kept forever while history_retention is 0.")]
+    HistoryRetentionDisabled,
+    #[msg("Historical records must be closed in auction ID order.")]
+    HistoryClosedOutOfOrder,
+    #[msg("The historical record is still within its retention window.")]
+    HistoryRetentionNotElapsed,
+    #[msg("Only the house created by migrate_legacy_config can take over pre-house accounts.")]
+    NotLegacyHouse,
+    #[msg("Start timestamp must not be in the past.")]
+    StartTimestampInPast,
+    #[msg("The auction has not started yet.")]
+    AuctionNotStarted,
+    #[msg("Account does not match the configured treasury.")]
+    InvalidTreasury,
+    #[msg("Revenue split has too many recipients.")]
+    TooManySplitRecipients,
+    #[msg("Split shares must be non-zero, go to a valid wallet and sum to 10000 bps.")]
+    InvalidSplitShares,
+    #[msg("The revenue split account is required while a split is configured.")]
+    MissingRevenueSplit,
+    #[msg("An account for every split recipient must be passed in remaining accounts.")]
+    MissingSplitRecipientAccounts,
+    #[msg("Split recipient account must be the recipient's wallet or associated token account, passed as writable.")]
+    InvalidSplitRecipientAccount,
+    #[msg("Burn share exceeds the maximum share of the winning bid.")]
+    BurnShareTooHigh,
+    #[msg("The default bidding token mint cannot be removed from the allowlist; change it with set_bidding_token first.")]
+    CannotRemoveBiddingTokenMint,
+    #[msg("The treasury must be a rent-exempt, non-executable account so that lamport proceeds can be paid to it.")]
+    TreasuryNotRentExempt,
     // More specific errors will be added based on PLAN_MIGRACION.md
 }
 
 // Events will be defined here
 #[event]
 pub struct BidPlaced {
+    pub house: Pubkey, // auction_config PDA of the auction house the event belongs to
+    pub auction_id: u64,
+    pub bidder: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub usd_value: u64,
+    pub resource_value: String,
+}
+
+// Emitted instead of BidPlaced when the highest bidder raises their own bid;
+// amount is the new total, of which only amount - previous_amount was transferred
+#[event]
+pub struct BidIncreased {
+    pub house: Pubkey,
     pub auction_id: u64,
     pub bidder: Pubkey,
+    pub mint: Pubkey,
+    pub previous_amount: u64,
     pub amount: u64,
+    pub usd_value: u64,
     pub resource_value: String,
 }
 
+// Emitted when an outbid amount is credited to the bidder's refund claim
 #[event]
 pub struct BidRefunded {
+    pub house: Pubkey,
     pub auction_id: u64,
     pub bidder: Pubkey, // The bidder being refunded
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RefundClaimed {
+    pub house: Pubkey,
+    pub bidder: Pubkey,
+    pub mint: Pubkey,
     pub amount: u64,
 }
 
+#[event]
+pub struct RefundLedgerClosed {
+    pub house: Pubkey,
+    pub mint: Pubkey,
+    pub rent_receiver: Pubkey,
+}
+
 #[event]
 pub struct AuctionEndedEvent { // Renamed to avoid conflict with AuctionError::AuctionEnded
+    pub house: Pubkey,
     pub auction_id: u64,
     pub winner: Pubkey,
+    pub mint: Pubkey,
     pub amount: u64,
     pub resource_value: String,
     pub end_timestamp: i64,
-}
\ No newline at end of file
+    pub reserve_not_met: bool,
+    pub proceeds_destination: Pubkey, // Treasury token account (or wallet for native SOL) paid, the revenue split table if split, default if none
+    pub burned_amount: u64, // Part of the winning bid burned from the vault, in base units of mint
+}
+
+#[event]
+pub struct ProceedsSplit {
+    pub house: Pubkey,
+    pub auction_id: u64,
+    pub mint: Pubkey,
+    pub payouts: Vec<SplitPayout>,
+}
+
+#[event]
+pub struct RevenueSplitUpdated {
+    pub house: Pubkey,
+    pub recipients: Vec<SplitRecipient>, // Empty when splitting was turned off
+}
+
+#[event]
+pub struct TreasuryUpdated {
+    pub house: Pubkey,
+    pub previous_treasury: Pubkey,
+    pub new_treasury: Pubkey,
+}
+
+#[event]
+pub struct AuctionCancelled {
+    pub house: Pubkey,
+    pub auction_id: u64,
+    pub reason: CancelReason,
+    pub refunded_bidder: Pubkey, // Pubkey::default() if the round had no bid
+    pub mint: Pubkey,
+    pub refunded_amount: u64,
+    pub paused: bool, // True if no new round was opened
+}
+
+#[event]
+pub struct AuctionExtended {
+    pub house: Pubkey,
+    pub auction_id: u64,
+    pub extension: u64, // Seconds added by this bid
+    pub new_end_timestamp: i64,
+    pub total_extension: u64,
+}
+
+#[event]
+pub struct AuthorityTransferProposed {
+    pub house: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferAccepted {
+    pub house: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub house: Pubkey,
+    pub authority: Pubkey,
+    pub cancelled_authority: Pubkey,
+}
+
+#[event]
+pub struct RoleGranted {
+    pub house: Pubkey,
+    pub holder: Pubkey,
+    pub role: Role,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub house: Pubkey,
+    pub holder: Pubkey,
+    pub role: Role,
+}
+
+#[event]
+pub struct CrankerRewardUpdated {
+    pub house: Pubkey,
+    pub reward_lamports: u64,
+    pub reward_bps: u16,
+}
+
+#[event]
+pub struct CrankerRewardPaid {
+    pub house: Pubkey,
+    pub auction_id: u64,
+    pub cranker: Pubkey,
+    pub lamports: u64,
+    pub mint: Pubkey, // Mint of bid_share, Pubkey::default() if there was no winning bid
+    pub bid_share: u64, // Share of the winning bid, in base units of mint (lamports for native SOL bids)
+}
+
+#[event]
+pub struct BiddingTokenUpdated {
+    pub house: Pubkey,
+    pub previous_mint: Pubkey,
+    pub new_mint: Pubkey,
+    pub effective_auction_id: u64, // First round that uses the new mint as its default
+}
+
+#[event]
+pub struct ReservePriceUpdated {
+    pub house: Pubkey,
+    pub reserve_price_usd: u64,
+    pub min_opening_bid_usd: u64,
+    pub effective_auction_id: u64, // First round that applies the new reserve and minimum opening bid
+}
+
+#[event]
+pub struct BurnBpsUpdated {
+    pub house: Pubkey,
+    pub burn_bps: u16,
+}
+
+#[event]
+pub struct MinBidIncreaseUpdated {
+    pub house: Pubkey,
+    pub new_bps: u16,
+    pub effective_auction_id: u64, // First round that enforces the new increase
+}
+
+#[event]
+pub struct NativeSolBiddingUpdated {
+    pub house: Pubkey,
+    pub enabled: bool,
+    pub effective_auction_id: u64, // First round that takes bids in the new currency
+}
+
+#[event]
+pub struct AuctionDurationUpdated {
+    pub house: Pubkey,
+    pub auction_duration: u64,
+    pub effective_auction_id: u64, // First round that runs for the new duration
+}
+
+#[event]
+pub struct RoundCooldownUpdated {
+    pub house: Pubkey,
+    pub round_cooldown: u64,
+    pub effective_auction_id: u64, // First round whose start waits for the new cooldown
+}
+
+#[event]
+pub struct DefaultResourceValueUpdated {
+    pub house: Pubkey,
+    pub default_resource_value: String,
+    pub effective_auction_id: u64, // First round that shows the new value when it ends without a winner
+}
+
+#[event]
+pub struct ResourceNameUpdated {
+    pub house: Pubkey,
+    pub resource_name: String,
+    pub effective_auction_id: u64, // First round auctioned under the new name
+}
+
+#[event]
+pub struct AntiSnipingUpdated {
+    pub house: Pubkey,
+    pub extension_window: u64,
+    pub extension_amount: u64,
+    pub max_total_extension: u64,
+    pub effective_auction_id: u64, // First round that applies the new soft close rules
+}
+
+#[event]
+pub struct AuctionsPaused {
+    pub house: Pubkey,
+    pub auction_id: u64,
+}
+
+#[event]
+pub struct AuctionsResumed {
+    pub house: Pubkey,
+    pub auction_id: u64,
+}
+
+#[event]
+pub struct AllowedMintAdded {
+    pub house: Pubkey,
+    pub mint: Pubkey,
+    pub price_usd: u64,
+    pub decimals: u8,
+}
+
+#[event]
+pub struct AllowedMintRemoved {
+    pub house: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct TokenPriceUpdated {
+    pub house: Pubkey,
+    pub mint: Pubkey,
+    pub new_price_usd: u64,
+}
+
+#[event]
+pub struct VetoWindowUpdated {
+    pub house: Pubkey,
+    pub veto_window: u64,
+}
+
+#[event]
+pub struct ResourceValueVetoed {
+    pub house: Pubkey,
+    pub auction_id: u64,
+    pub moderator: Pubkey,
+    pub winner: Pubkey,
+    pub vetoed_value: String,
+    pub mint: Pubkey,
+    pub refund_amount: u64,
+}
+
+#[event]
+pub struct HistoryRetentionUpdated {
+    pub house: Pubkey,
+    pub history_retention: u64,
+}
+
+#[event]
+pub struct HistoricalRecordClosed {
+    pub house: Pubkey,
+    pub auction_id: u64,
+    pub record_hash: [u8; 32],
+    pub chain_hash: [u8; 32],
+    pub rent_receiver: Pubkey,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub house: Pubkey,
+    pub account: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
+#[event]
+pub struct LegacyAccountMoved {
+    pub house: Pubkey,
+    pub legacy_account: Pubkey, // Closed account at the pre-house seeds
+    pub account: Pubkey, // Its counterpart in the house
+}
+
+#[event]
+pub struct FundsWithdrawn {
+    pub house: Pubkey,
+    pub mint: Pubkey,
+    pub to: Pubkey, // Destination token account
+    pub amount: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usd_value_normalizes_by_decimals_and_rounds_down() {
+        // 1.5 tokens with 6 decimals at 2 USD
+        assert_eq!(usd_value(1_500_000, 200_000_000, 6).unwrap(), 300_000_000);
+        // 1 base unit of a 9 decimal token at 1 USD is below the smallest USD unit
+        assert_eq!(usd_value(1, 100_000_000, 9).unwrap(), 0);
+        assert_eq!(usd_value(19, 100_000_000, 9).unwrap(), 1);
+        assert_eq!(usd_value(7, 3, 0).unwrap(), 21);
+    }
+
+    #[test]
+    fn usd_value_rejects_overflow() {
+        assert!(usd_value(u64::MAX, u64::MAX, 0).is_err());
+        assert!(usd_value(u64::MAX, 2, 0).is_err());
+        // 10^39 does not fit in a u128
+        assert!(usd_value(1, 1, 39).is_err());
+        // (2^64 - 1)^2 / 10^38, the largest product over the largest divisor that fits
+        assert_eq!(usd_value(u64::MAX, u64::MAX, 38).unwrap(), 3);
+    }
+
+    fn recipient_token_account(state: spl_token_2022::state::AccountState, require_incoming_memos: Option<bool>) -> Vec<u8> {
+        use spl_token_2022::extension::{memo_transfer::MemoTransfer, BaseStateWithExtensionsMut, StateWithExtensionsMut};
+        let extension_types = if require_incoming_memos.is_some() { vec![ExtensionType::MemoTransfer] } else { vec![] };
+        let account_len = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(&extension_types).unwrap();
+        let mut data = vec![0; account_len];
+        let mut token_account_state = StateWithExtensionsMut::<spl_token_2022::state::Account>::unpack_uninitialized(&mut data).unwrap();
+        token_account_state.base = spl_token_2022::state::Account {
+            mint: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            state,
+            ..Default::default()
+        };
+        token_account_state.pack_base();
+        if let Some(required) = require_incoming_memos {
+            token_account_state.init_extension::<MemoTransfer>(true).unwrap().require_incoming_transfer_memos = required.into();
+        }
+        token_account_state.init_account_type().unwrap();
+        data
+    }
+
+    fn can_receive(mut data: Vec<u8>, owner: &Pubkey) -> bool {
+        let key = Pubkey::new_unique();
+        let mut lamports = 1_000_000;
+        let token_account = AccountInfo::new(&key, false, true, &mut lamports, &mut data, owner, false, 0);
+        can_receive_tokens(&token_account, &spl_token_2022::ID)
+    }
+
+    #[test]
+    fn split_recipient_token_account_must_take_transfers_without_a_memo() {
+        use spl_token_2022::state::AccountState;
+        assert!(can_receive(recipient_token_account(AccountState::Initialized, None), &spl_token_2022::ID));
+        assert!(can_receive(recipient_token_account(AccountState::Initialized, Some(false)), &spl_token_2022::ID));
+        assert!(!can_receive(recipient_token_account(AccountState::Initialized, Some(true)), &spl_token_2022::ID));
+        assert!(!can_receive(recipient_token_account(AccountState::Frozen, None), &spl_token_2022::ID));
+        // Closed accounts are handed back to the system program
+        assert!(!can_receive(recipient_token_account(AccountState::Initialized, None), &System::id()));
+    }
+
+    #[test]
+    fn min_next_bid_rounds_up() {
+        let mut state = AuctionState { highest_bid_usd_value: 1_001, min_bid_increase_bps: 1_000, ..AuctionState::default() };
+        // 1_001 * 1.1 = 1_101.1
+        assert_eq!(state.min_next_bid_usd_value().unwrap(), 1_102);
+        state.highest_bid_usd_value = 1_000;
+        assert_eq!(state.min_next_bid_usd_value().unwrap(), 1_100);
+        state.min_bid_increase_bps = 0;
+        assert_eq!(state.min_next_bid_usd_value().unwrap(), 1_000);
+        state.highest_bid_usd_value = 0;
+        state.min_bid_increase_bps = 1_000;
+        assert_eq!(state.min_next_bid_usd_value().unwrap(), 0);
+    }
+
+    #[test]
+    fn min_next_bid_rejects_overflow() {
+        let state = AuctionState { highest_bid_usd_value: u64::MAX, min_bid_increase_bps: 1, ..AuctionState::default() };
+        assert!(state.min_next_bid_usd_value().is_err());
+    }
+
+    #[test]
+    fn cranker_reward_rounds_down() {
+        let auction_config = AuctionConfig { cranker_reward_bps: 250, ..AuctionConfig::default() };
+        assert_eq!(auction_config.cranker_reward_share(1_000).unwrap(), 25);
+        assert_eq!(auction_config.cranker_reward_share(39).unwrap(), 0);
+        assert_eq!(auction_config.cranker_reward_share(41).unwrap(), 1);
+        assert_eq!(auction_config.cranker_reward_share(0).unwrap(), 0);
+    }
+
+    #[test]
+    fn cranker_reward_does_not_overflow_on_large_bids() {
+        let auction_config = AuctionConfig { cranker_reward_bps: MAX_CRANKER_REWARD_BPS, ..AuctionConfig::default() };
+        assert_eq!(auction_config.cranker_reward_share(u64::MAX).unwrap(), u64::MAX / 10);
+    }
+
+    fn open_state(end_timestamp: i64, extension_window: u64, extension_amount: u64, max_total_extension: u64) -> AuctionState {
+        AuctionState {
+            end_timestamp,
+            extension_window,
+            extension_amount,
+            max_total_extension,
+            ..AuctionState::default()
+        }
+    }
+
+    #[test]
+    fn late_bid_extends_until_the_cap() {
+        let mut state = open_state(1_000, 60, 30, 50);
+        assert_eq!(state.extend_for_late_bid(950).unwrap(), 30);
+        assert_eq!((state.end_timestamp, state.total_extension), (1_030, 30));
+        // Only 20 seconds of the cap are left
+        assert_eq!(state.extend_for_late_bid(1_000).unwrap(), 20);
+        assert_eq!((state.end_timestamp, state.total_extension), (1_050, 50));
+        assert_eq!(state.extend_for_late_bid(1_049).unwrap(), 0);
+        assert_eq!(state.end_timestamp, 1_050);
+    }
+
+    #[test]
+    fn early_bid_or_disabled_soft_close_does_not_extend() {
+        let mut state = open_state(1_000, 60, 30, 300);
+        assert_eq!(state.extend_for_late_bid(939).unwrap(), 0);
+        // Exactly at the window edge still extends
+        assert_eq!(state.extend_for_late_bid(940).unwrap(), 30);
+
+        let mut disabled = open_state(1_000, 0, 30, 300);
+        assert_eq!(disabled.extend_for_late_bid(999).unwrap(), 0);
+        let mut disabled = open_state(1_000, 60, 0, 300);
+        assert_eq!(disabled.extend_for_late_bid(999).unwrap(), 0);
+        assert_eq!(disabled.end_timestamp, 1_000);
+    }
+
+    #[test]
+    fn late_bid_rejects_overflow() {
+        let mut state = open_state(i64::MAX, 60, 30, 300);
+        assert!(state.extend_for_late_bid(i64::MAX - 10).is_err());
+        let mut state = open_state(i64::MIN, 60, 30, 300);
+        assert!(state.extend_for_late_bid(1).is_err());
+    }
+
+    fn split(shares: &[u16]) -> RevenueSplit {
+        RevenueSplit {
+            recipients: shares
+                .iter()
+                .map(|&bps| SplitRecipient { wallet: Pubkey::new_unique(), bps })
+                .collect(),
+            ..RevenueSplit::default()
+        }
+    }
+
+    #[test]
+    fn payouts_give_the_remainder_to_the_last_recipient() {
+        let payouts = split(&[3_333, 3_333, 3_334]).payouts(100).unwrap();
+        let amounts: Vec<u64> = payouts.iter().map(|payout| payout.amount).collect();
+        assert_eq!(amounts, vec![33, 33, 34]);
+        assert!(payouts.iter().all(|payout| !payout.paid_to_treasury));
+
+        let amounts: Vec<u64> = split(&[5_000, 5_000]).payouts(1).unwrap().iter().map(|payout| payout.amount).collect();
+        assert_eq!(amounts, vec![0, 1]);
+    }
+
+    #[test]
+    fn payouts_add_up_to_the_proceeds() {
+        let revenue_split = split(&[1, 2_499, 7_500]);
+        for proceeds in [0, 1, 9_999, 10_001, u64::MAX] {
+            let payouts = revenue_split.payouts(proceeds).unwrap();
+            assert_eq!(payouts.iter().map(|payout| payout.amount as u128).sum::<u128>(), proceeds as u128);
+        }
+        assert!(split(&[]).payouts(100).unwrap().is_empty());
+        assert_eq!(split(&[BPS_DENOMINATOR as u16]).payouts(u64::MAX).unwrap()[0].amount, u64::MAX);
+    }
+
+    #[test]
+    fn payouts_reject_shares_above_the_proceeds() {
+        // RevenueSplit is validated to sum to BPS_DENOMINATOR; a table that doesn't cannot overpay
+        assert!(split(&[6_000, 6_000, 0]).payouts(100).is_err());
+    }
+
+    #[test]
+    fn burn_rounds_down() {
+        let auction_config = AuctionConfig { burn_bps: 1_500, ..AuctionConfig::default() };
+        assert_eq!(auction_config.burn_share(1_000).unwrap(), 150);
+        assert_eq!(auction_config.burn_share(13).unwrap(), 1);
+        assert_eq!(auction_config.burn_share(0).unwrap(), 0);
+    }
+
+    #[test]
+    fn burn_and_cranker_reward_do_not_overflow_on_large_bids() {
+        let auction_config = AuctionConfig {
+            cranker_reward_bps: MAX_CRANKER_REWARD_BPS,
+            burn_bps: MAX_BURN_BPS,
+            ..AuctionConfig::default()
+        };
+        let cranker_reward = auction_config.cranker_reward_share(u64::MAX).unwrap();
+        let burned = auction_config.burn_share(u64::MAX).unwrap();
+        assert_eq!(burned, ((u64::MAX as u128) * 9 / 10) as u64);
+        assert!(cranker_reward.checked_add(burned).is_some());
+    }
+
+    #[test]
+    fn open_rejects_a_duration_beyond_i64() {
+        let mut state = AuctionState::default();
+        let auction_config = AuctionConfig { auction_duration: i64::MAX as u64 + 1, ..AuctionConfig::default() };
+        assert!(state.open(1, 0, &auction_config).is_err());
+
+        let auction_config = AuctionConfig { auction_duration: 3_600, ..AuctionConfig::default() };
+        state.open(1, 1_000, &auction_config).unwrap();
+        assert_eq!((state.start_timestamp, state.end_timestamp), (1_000, 4_600));
+    }
+
+    fn mint_with_default_account_state(state: Option<spl_token_2022::state::AccountState>) -> Vec<u8> {
+        use spl_token_2022::extension::{BaseStateWithExtensionsMut, StateWithExtensionsMut};
+        let extension_types = if state.is_some() { vec![ExtensionType::DefaultAccountState] } else { vec![] };
+        let mint_len = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&extension_types).unwrap();
+        let mut data = vec![0; mint_len];
+        let mut mint_state = StateWithExtensionsMut::<spl_token_2022::state::Mint>::unpack_uninitialized(&mut data).unwrap();
+        mint_state.base = spl_token_2022::state::Mint { decimals: 6, is_initialized: true, ..Default::default() };
+        mint_state.pack_base();
+        if let Some(state) = state {
+            mint_state.init_extension::<DefaultAccountState>(true).unwrap().state = state as u8;
+        }
+        mint_state.init_account_type().unwrap();
+        data
+    }
+
+    fn is_supported(mut data: Vec<u8>) -> bool {
+        let key = Pubkey::new_unique();
+        let mut lamports = 1_000_000;
+        let mint = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &spl_token_2022::ID, false, 0);
+        require_supported_mint(&mint).is_ok()
+    }
+
+    #[test]
+    fn supported_mint_rejects_a_frozen_default_account_state() {
+        use spl_token_2022::state::AccountState;
+        assert!(is_supported(mint_with_default_account_state(None)));
+        assert!(is_supported(mint_with_default_account_state(Some(AccountState::Initialized))));
+        assert!(!is_supported(mint_with_default_account_state(Some(AccountState::Frozen))));
+    }
+
+    #[test]
+    fn legacy_auction_state_rejects_a_duration_beyond_i64() {
+        let v0 = AuctionStateV0 {
+            id: 7,
+            start_timestamp: 1_000,
+            highest_bidder: Pubkey::default(),
+            highest_bid_amount: 0,
+            highest_bid_resource_value: String::new(),
+        };
+        let data = [AuctionState::DISCRIMINATOR, &borsh::to_vec(&v0).unwrap()].concat();
+        let auction_config = AuctionConfig { auction_duration: i64::MAX as u64 + 1, ..AuctionConfig::default() };
+        assert!(AuctionState::from_v0(&data, &auction_config, None).is_err());
+
+        let auction_config = AuctionConfig { auction_duration: 3_600, ..AuctionConfig::default() };
+        assert_eq!(AuctionState::from_v0(&data, &auction_config, None).unwrap().end_timestamp, 4_600);
+    }
+}
@@ -0,0 +1,270 @@
+// Refund claim accounting through the program's instructions: outbid amounts are credited to the
+// outbid bidder's RefundClaim and the mint's RefundLedger, and claim_refund pays them out of the vault.
+
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token;
+use solana_program_test::{processor, tokio, BanksClient, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program,
+    transaction::Transaction,
+};
+use token_auction_program::{RefundClaim, RefundLedger};
+
+const HOUSE_ID: u64 = 7;
+const DECIMALS: u8 = 6;
+const ONE_TOKEN: u64 = 1_000_000;
+
+// Anchor's entry ties the accounts slice to the lifetime of the AccountInfos, which the
+// processor! signature does not; the copies share their data with the originals.
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    token_auction_program::entry(program_id, accounts, data)
+}
+
+struct House {
+    authority: Keypair,
+    mint: Pubkey,
+    auction_config: Pubkey,
+    auction_state: Pubkey,
+    vault: Pubkey,
+    allowed_mint: Pubkey,
+    refund_ledger: Pubkey,
+}
+
+impl House {
+    fn new(authority: Keypair, mint: Pubkey) -> Self {
+        let program_id = token_auction_program::ID;
+        let (auction_config, _) = Pubkey::find_program_address(
+            &[b"auction_config", authority.pubkey().as_ref(), &HOUSE_ID.to_le_bytes()],
+            &program_id,
+        );
+        let (auction_state, _) =
+            Pubkey::find_program_address(&[b"active_auction", auction_config.as_ref(), &1u64.to_le_bytes()], &program_id);
+        let (vault, _) =
+            Pubkey::find_program_address(&[b"program_token_account", auction_config.as_ref(), mint.as_ref()], &program_id);
+        let (allowed_mint, _) =
+            Pubkey::find_program_address(&[b"allowed_mint", auction_config.as_ref(), mint.as_ref()], &program_id);
+        let (refund_ledger, _) =
+            Pubkey::find_program_address(&[b"refund_ledger", auction_config.as_ref(), mint.as_ref()], &program_id);
+        House { authority, mint, auction_config, auction_state, vault, allowed_mint, refund_ledger }
+    }
+
+    fn refund_claim(&self, bidder: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"refund_claim", self.auction_config.as_ref(), bidder.as_ref(), self.mint.as_ref()],
+            &token_auction_program::ID,
+        )
+        .0
+    }
+
+    fn initialize_auction(&self, payer: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: token_auction_program::ID,
+            accounts: token_auction_program::accounts::InitializeAuction {
+                auction_config: self.auction_config,
+                auction_state: self.auction_state,
+                program_token_account: self.vault,
+                allowed_mint: self.allowed_mint,
+                bidding_token_mint: self.mint,
+                authority: self.authority.pubkey(),
+                payer: *payer,
+                system_program: system_program::ID,
+                token_program: spl_token::ID,
+                rent: solana_sdk::sysvar::rent::ID,
+            }
+            .to_account_metas(None),
+            data: token_auction_program::instruction::InitializeAuction {
+                house_id: HOUSE_ID,
+                resource_name: "banner".to_string(),
+                default_resource_value: "default".to_string(),
+                auction_duration: 3_600,
+                start_timestamp: None,
+                bidding_token_price_usd: 100_000_000, // 1 USD
+            }
+            .data(),
+        }
+    }
+
+    // `previous_bidder` is the highest bidder being outbid, if any
+    fn place_bid(&self, bidder: &Pubkey, bidder_token_account: &Pubkey, amount: u64, previous_bidder: Option<&Pubkey>) -> Instruction {
+        Instruction {
+            program_id: token_auction_program::ID,
+            accounts: token_auction_program::accounts::PlaceBid {
+                auction_config: self.auction_config,
+                auction_state: self.auction_state,
+                bid_mint: Some(self.mint),
+                allowed_mint: Some(self.allowed_mint),
+                program_token_account: Some(self.vault),
+                bidder_token_account: Some(*bidder_token_account),
+                sol_escrow: None,
+                previous_bidder_refund_claim: previous_bidder.map(|previous_bidder| self.refund_claim(previous_bidder)),
+                previous_bid_refund_ledger: previous_bidder.map(|_| self.refund_ledger),
+                bidder_authority: *bidder,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: token_auction_program::instruction::PlaceBid { amount, resource_value: "https://example.com".to_string() }.data(),
+        }
+    }
+
+    fn claim_refund(&self, bidder: &Pubkey, rent_payer: &Pubkey, destination_token_account: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: token_auction_program::ID,
+            accounts: token_auction_program::accounts::ClaimRefund {
+                auction_config: self.auction_config,
+                refund_claim: self.refund_claim(bidder),
+                rent_payer: *rent_payer,
+                refund_ledger: self.refund_ledger,
+                program_token_account: Some(self.vault),
+                mint: Some(self.mint),
+                destination_token_account: Some(*destination_token_account),
+                sol_escrow: None,
+                bidder: *bidder,
+                token_program: spl_token::ID,
+            }
+            .to_account_metas(None),
+            data: token_auction_program::instruction::ClaimRefund {}.data(),
+        }
+    }
+}
+
+async fn send(context: &mut ProgramTestContext, instructions: &[Instruction], signers: &[&Keypair]) {
+    try_send(context, instructions, signers).await.unwrap();
+}
+
+async fn try_send(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), solana_program_test::BanksClientError> {
+    let blockhash = context.last_blockhash;
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(instructions, Some(&context.payer.pubkey()), &all_signers, blockhash);
+    context.banks_client.process_transaction(transaction).await
+}
+
+async fn create_account(context: &mut ProgramTestContext, account: &Keypair, space: usize, owner: &Pubkey) -> Instruction {
+    let lamports = context.banks_client.get_rent().await.unwrap().minimum_balance(space);
+    system_instruction::create_account(&context.payer.pubkey(), &account.pubkey(), lamports, space as u64, owner)
+}
+
+// A funded bidder wallet with a token account holding `tokens` of `mint`
+async fn create_bidder(context: &mut ProgramTestContext, mint: &Keypair, tokens: u64) -> (Keypair, Pubkey) {
+    let bidder = Keypair::new();
+    let token_account = Keypair::new();
+    let create_token_account = create_account(context, &token_account, spl_token::state::Account::LEN, &spl_token::ID).await;
+    let instructions = [
+        system_instruction::transfer(&context.payer.pubkey(), &bidder.pubkey(), 1_000_000_000),
+        create_token_account,
+        spl_token::instruction::initialize_account3(&spl_token::ID, &token_account.pubkey(), &mint.pubkey(), &bidder.pubkey()).unwrap(),
+        spl_token::instruction::mint_to(&spl_token::ID, &mint.pubkey(), &token_account.pubkey(), &context.payer.pubkey(), &[], tokens)
+            .unwrap(),
+    ];
+    send(context, &instructions, &[&token_account]).await;
+    (bidder, token_account.pubkey())
+}
+
+async fn token_balance(banks_client: &mut BanksClient, token_account: &Pubkey) -> u64 {
+    let account = banks_client.get_account(*token_account).await.unwrap().unwrap();
+    spl_token::state::Account::unpack(&account.data).unwrap().amount
+}
+
+async fn fetch<T: AccountDeserialize>(banks_client: &mut BanksClient, address: &Pubkey) -> T {
+    let account = banks_client.get_account(*address).await.unwrap().unwrap();
+    T::try_deserialize(&mut &account.data[..]).unwrap()
+}
+
+async fn start_house() -> (ProgramTestContext, House, Keypair) {
+    let program_test = ProgramTest::new("token_auction_program", token_auction_program::ID, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+
+    let mint = Keypair::new();
+    let create_mint = create_account(&mut context, &mint, spl_token::state::Mint::LEN, &spl_token::ID).await;
+    let initialize_mint =
+        spl_token::instruction::initialize_mint2(&spl_token::ID, &mint.pubkey(), &context.payer.pubkey(), None, DECIMALS).unwrap();
+    send(&mut context, &[create_mint, initialize_mint], &[&mint]).await;
+
+    let house = House::new(Keypair::new(), mint.pubkey());
+    let initialize_auction = house.initialize_auction(&context.payer.pubkey());
+    send(&mut context, &[initialize_auction], &[&house.authority]).await;
+    (context, house, mint)
+}
+
+#[tokio::test(crate = "solana_program_test::tokio")]
+async fn outbid_amounts_are_credited_to_refund_claims_and_claimed_from_the_vault() {
+    let (mut context, house, mint) = start_house().await;
+    let (alice, alice_tokens) = create_bidder(&mut context, &mint, 10 * ONE_TOKEN).await;
+    let (bob, bob_tokens) = create_bidder(&mut context, &mint, 10 * ONE_TOKEN).await;
+
+    send(&mut context, &[house.place_bid(&alice.pubkey(), &alice_tokens, ONE_TOKEN, None)], &[&alice]).await;
+    send(&mut context, &[house.place_bid(&bob.pubkey(), &bob_tokens, 2 * ONE_TOKEN, Some(&alice.pubkey()))], &[&bob]).await;
+
+    // Bob funded Alice's claim and the ledger by outbidding her
+    let alice_claim: RefundClaim = fetch(&mut context.banks_client, &house.refund_claim(&alice.pubkey())).await;
+    assert_eq!((alice_claim.bidder, alice_claim.mint, alice_claim.amount), (alice.pubkey(), house.mint, ONE_TOKEN));
+    assert_eq!(alice_claim.rent_payer, bob.pubkey());
+    let ledger: RefundLedger = fetch(&mut context.banks_client, &house.refund_ledger).await;
+    assert_eq!((ledger.pending_amount, ledger.rent_payer), (ONE_TOKEN, bob.pubkey()));
+    assert_eq!(token_balance(&mut context.banks_client, &house.vault).await, 3 * ONE_TOKEN);
+
+    send(&mut context, &[house.place_bid(&alice.pubkey(), &alice_tokens, 3 * ONE_TOKEN, Some(&bob.pubkey()))], &[&alice]).await;
+
+    // Alice's earlier refund is still owed next to Bob's
+    let alice_claim: RefundClaim = fetch(&mut context.banks_client, &house.refund_claim(&alice.pubkey())).await;
+    assert_eq!(alice_claim.amount, ONE_TOKEN);
+    let bob_claim: RefundClaim = fetch(&mut context.banks_client, &house.refund_claim(&bob.pubkey())).await;
+    assert_eq!((bob_claim.amount, bob_claim.rent_payer), (2 * ONE_TOKEN, alice.pubkey()));
+    let ledger: RefundLedger = fetch(&mut context.banks_client, &house.refund_ledger).await;
+    assert_eq!(ledger.pending_amount, 3 * ONE_TOKEN);
+    assert_eq!(token_balance(&mut context.banks_client, &house.vault).await, 6 * ONE_TOKEN);
+
+    // Claims are paid to any token account of the mint and close to whoever funded them
+    let bob_lamports_before = context.banks_client.get_balance(bob.pubkey()).await.unwrap();
+    send(&mut context, &[house.claim_refund(&alice.pubkey(), &bob.pubkey(), &alice_tokens)], &[&alice]).await;
+    assert_eq!(token_balance(&mut context.banks_client, &alice_tokens).await, 10 * ONE_TOKEN - ONE_TOKEN - 3 * ONE_TOKEN + ONE_TOKEN);
+    assert!(context.banks_client.get_account(house.refund_claim(&alice.pubkey())).await.unwrap().is_none());
+    assert!(context.banks_client.get_balance(bob.pubkey()).await.unwrap() > bob_lamports_before);
+
+    send(&mut context, &[house.claim_refund(&bob.pubkey(), &alice.pubkey(), &bob_tokens)], &[&bob]).await;
+    assert_eq!(token_balance(&mut context.banks_client, &bob_tokens).await, 10 * ONE_TOKEN);
+
+    // Only the active highest bid is left in the vault and nothing is owed any more
+    let ledger: RefundLedger = fetch(&mut context.banks_client, &house.refund_ledger).await;
+    assert_eq!(ledger.pending_amount, 0);
+    assert_eq!(token_balance(&mut context.banks_client, &house.vault).await, 3 * ONE_TOKEN);
+}
+
+#[tokio::test(crate = "solana_program_test::tokio")]
+async fn a_refund_can_only_be_claimed_once_and_only_by_its_bidder() {
+    let (mut context, house, mint) = start_house().await;
+    let (alice, alice_tokens) = create_bidder(&mut context, &mint, 10 * ONE_TOKEN).await;
+    let (bob, bob_tokens) = create_bidder(&mut context, &mint, 10 * ONE_TOKEN).await;
+
+    send(&mut context, &[house.place_bid(&alice.pubkey(), &alice_tokens, ONE_TOKEN, None)], &[&alice]).await;
+    send(&mut context, &[house.place_bid(&bob.pubkey(), &bob_tokens, 2 * ONE_TOKEN, Some(&alice.pubkey()))], &[&bob]).await;
+
+    // Bob cannot withdraw Alice's refund: the claim is derived from the signing bidder
+    let mut stolen_claim = house.claim_refund(&bob.pubkey(), &bob.pubkey(), &bob_tokens);
+    stolen_claim.accounts[1].pubkey = house.refund_claim(&alice.pubkey());
+    assert!(try_send(&mut context, &[stolen_claim], &[&bob]).await.is_err());
+
+    send(&mut context, &[house.claim_refund(&alice.pubkey(), &bob.pubkey(), &alice_tokens)], &[&alice]).await;
+    // A fresh blockhash, so that the retry is not rejected as a duplicate transaction
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    assert!(try_send(&mut context, &[house.claim_refund(&alice.pubkey(), &bob.pubkey(), &alice_tokens)], &[&alice])
+        .await
+        .is_err());
+
+    assert_eq!(token_balance(&mut context.banks_client, &alice_tokens).await, 10 * ONE_TOKEN);
+    let ledger: RefundLedger = fetch(&mut context.banks_client, &house.refund_ledger).await;
+    assert_eq!(ledger.pending_amount, 0);
+    assert_eq!(token_balance(&mut context.banks_client, &house.vault).await, 2 * ONE_TOKEN);
+}